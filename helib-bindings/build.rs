@@ -15,6 +15,26 @@ const INCLUDE_FOLDER: &str = "helib_pack/include";
 const HELIB_LIB: &str = "helib";
 const GMP_LIB: &str = "gmp";
 const NTL_LIB: &str = "ntl";
+const EXT_LIB: &str = "helib_ext";
+const EXT_SOURCES: &[&str] = &[
+    "src/ext/c_ext_error.cpp",
+    "src/ext/c_ext_checked.cpp",
+    "src/ext/c_ext_context.cpp",
+    "src/ext/c_ext_ctxt.cpp",
+    "src/ext/c_ext_keys.cpp",
+    "src/ext/c_ext_galois.cpp",
+    "src/ext/c_ext_seeded.cpp",
+    "src/ext/c_ext_rng.cpp",
+    "src/ext/c_ext_zz.cpp",
+    "src/ext/c_ext_zzx.cpp",
+];
+// Classes whose internals the extensions access through helib::ExtAccess, see src/ext/c_ext_access.h
+const FRIEND_CLASSES: &[(&str, &str)] = &[
+    ("include/helib/Ctxt.h", "Ctxt"),
+    ("include/helib/keys.h", "SecKey"),
+];
+const FRIEND_DECLARATION: &str =
+    "\n  friend struct ExtAccess; // Added by helib-bindings/build.rs\n";
 
 #[allow(dead_code)]
 fn checkout_commit(repo: &Repository, commit: &str) -> Result<()> {
@@ -45,6 +65,42 @@ fn download(out_dir: &Path) -> Result<()> {
     Ok(())
 }
 
+// Position after the opening brace of the definition of class name, skipping forward declarations
+fn class_body_start(source: &str, name: &str) -> Option<usize> {
+    let pattern = format!("class {name}");
+    let mut offset = 0;
+    while let Some(pos) = source[offset..].find(&pattern) {
+        let start = offset + pos + pattern.len();
+        offset = start;
+        let rest = &source[start..];
+        if rest.starts_with(|c: char| c.is_alphanumeric() || c == '_') {
+            continue;
+        }
+        let end = rest.find([';', '{'])?;
+        if rest[end..].starts_with('{') {
+            return Some(start + end + 1);
+        }
+    }
+    None
+}
+
+// Declares helib::ExtAccess a friend of the classes in FRIEND_CLASSES. HElib keeps the parts,
+// noise bound and key-switching of a Ctxt as well as the secret key polynomials private.
+fn patch(out_dir: &Path) -> Result<()> {
+    for (header, class) in FRIEND_CLASSES {
+        let path = out_dir.join(HELIB_FOLDER).join(header);
+        let mut source = std::fs::read_to_string(&path)?;
+        if source.contains(FRIEND_DECLARATION) {
+            continue;
+        }
+        let pos = class_body_start(&source, class)
+            .ok_or_else(|| anyhow!("Definition of class {class} not found in {header}"))?;
+        source.insert_str(pos, FRIEND_DECLARATION);
+        std::fs::write(&path, source)?;
+    }
+    Ok(())
+}
+
 fn build(out_dir: &Path) -> Result<()> {
     #[cfg(feature = "clang")]
     {
@@ -68,6 +124,19 @@ fn build(out_dir: &Path) -> Result<()> {
     Ok(())
 }
 
+fn build_ext(out_dir: &Path) -> Result<()> {
+    let include_dir = out_dir.join(INSTALL_FOLDER).join(INCLUDE_FOLDER);
+
+    // Has to be compiled before linking helib, such that the static libraries are linked in the correct order
+    cc::Build::new()
+        .cpp(true)
+        .std("c++17")
+        .include(include_dir)
+        .files(EXT_SOURCES)
+        .try_compile(EXT_LIB)?;
+    Ok(())
+}
+
 fn link(out_dir: &Path) -> Result<()> {
     let lib_dir = out_dir.join(INSTALL_FOLDER).join(LIB_FOLDER);
 
@@ -121,8 +190,10 @@ fn main() {
     let out_dir = PathBuf::from(env::var("OUT_DIR").expect("getting environment var failed"));
     if !exist(&out_dir) {
         download(&out_dir).expect("Download failed");
+        patch(&out_dir).expect("Patching HElib failed");
         build(&out_dir).expect("Build failed");
     }
+    build_ext(&out_dir).expect("Building extensions failed");
     link(&out_dir).expect("Link failed");
     bindgen(&out_dir).expect("Bindgen failed");
 }
@@ -0,0 +1,43 @@
+#pragma once
+
+// Access to internals HElib keeps private. build.rs declares helib::ExtAccess
+// a friend of helib::Ctxt and helib::SecKey when it fetches HElib. Not part of
+// the C interface itself, so this header is only included from the .cpp files.
+
+#include <helib/helib.h>
+
+#include <vector>
+
+namespace helib
+{
+struct ExtAccess
+{
+    // The secret key polynomial with the given id
+    static const DoubleCRT &secret_key(const SecKey &sk, long id)
+    {
+        return sk.sKeys.at(id);
+    }
+
+    // Replaces the parts of c by the canonical (b, a) with respect to secret key 0,
+    // i.e., b + a * s decrypts to the plaintext
+    static void set_parts(Ctxt &c, const DoubleCRT &b, const DoubleCRT &a, const NTL::xdouble &noise)
+    {
+        c.parts.assign({CtxtPart(b, SKHandle(0, 1, 0)), CtxtPart(a, SKHandle(1, 1, 0))});
+        c.primeSet = b.getIndexSet();
+        c.noiseBound = noise;
+    }
+
+    static void set_noise_bound(Ctxt &c, const NTL::xdouble &noise) { c.noiseBound = noise; }
+
+    static void add_part(Ctxt &c, const DoubleCRT &part, const SKHandle &handle)
+    {
+        c.addPart(part, handle, true);
+    }
+
+    // Adds the key-switched digits of a decomposed part, see helib::BasicAutomorphPrecon
+    static void key_switch_digits(Ctxt &c, const KeySwitch &W, std::vector<DoubleCRT> &digits)
+    {
+        c.keySwitchDigits(W, digits);
+    }
+};
+} // namespace helib
@@ -0,0 +1,389 @@
+#include "c_ext_checked.h"
+#include "c_ext_utils.h"
+
+#include <helib/c_context.h>
+#include <helib/c_ctxt.h>
+#include <helib/c_galoiskey2k.h>
+#include <helib/c_ntl_ZZ.h>
+#include <helib/c_ntl_ZZX.h>
+#include <helib/c_pubkey.h>
+#include <helib/c_seckey.h>
+
+#include <helib/helib.h>
+
+#include <NTL/ZZ.h>
+
+#include <cstdio>
+
+using namespace helib_ext;
+
+namespace
+{
+// Calls a function of the HElib C API. A failure is reported with the name of the
+// function and its code, unless an exception thrown inside carries a message.
+// Codes other than kPointer and kInvalidArg are reported as category.
+template <typename Call>
+long guarded(const char *name, Call call, long category = kException)
+{
+    try
+    {
+        long ret = call();
+        if (ret == kOk)
+            return kOk;
+
+        char what[128];
+        std::snprintf(what, sizeof(what), "%s failed with %#lx", name, static_cast<unsigned long>(ret));
+        if (ret == kPointer || ret == kInvalidArg)
+            return report(ret, what);
+        return report(category, what);
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
+
+// Checks that the HElib objects a and b belong to the same context. Null pointers
+// are left to the called function to report.
+template <typename A, typename B>
+long check_same_context(void *a, void *b)
+{
+    if (a == nullptr || b == nullptr)
+        return kOk;
+    if (&static_cast<const A *>(a)->getContext() != &static_cast<const B *>(b)->getContext())
+        return report(kContextMismatch, "The objects belong to different contexts");
+    return kOk;
+}
+
+long check_ctxts(void *a, void *b)
+{
+    return check_same_context<helib::Ctxt, helib::Ctxt>(a, b);
+}
+
+long check_noise_budget(void *c)
+{
+    long budget = 0;
+    long ret = ext_ctxt_get_noise_budget(c, &budget);
+    if (ret != kOk)
+        return ret;
+    if (budget <= 0)
+        return report(kNoiseBudget, "The ciphertext has no noise budget left");
+    return kOk;
+}
+} // namespace
+
+// Context
+
+long ext_context_build(void **ctx, long m, void *p, long bits)
+{
+    NTL::ZZ *modulus = static_cast<NTL::ZZ *>(p);
+    if (modulus != nullptr && NTL::GCD(*modulus, NTL::ZZ(m)) != 1)
+        return report(kInvalidArg, "The plaintext modulus p is not coprime to m");
+
+    // HElib rejects the parameters, e.g., an m it cannot factor into a usable algebra
+    return guarded("context_build", [&] { return context_build(ctx, m, p, bits); }, kInvalidArg);
+}
+
+long ext_context_get_security_level(void *ctx, double *res)
+{
+    return guarded("context_get_security_level", [&] { return context_get_security_level(ctx, res); });
+}
+
+long ext_context_printout(void *ctx)
+{
+    return guarded("context_printout", [&] { return context_printout(ctx); });
+}
+
+// Keys
+
+long ext_seckey_build(void **sk, void *ctx)
+{
+    return guarded("seckey_build", [&] { return seckey_build(sk, ctx); });
+}
+
+long ext_pubkey_from_seckey(void **pk, void *sk)
+{
+    return guarded("pubkey_from_seckey", [&] { return pubkey_from_seckey(pk, sk); });
+}
+
+long ext_seckey_encrypt(void **c, void *sk, void *zz)
+{
+    return guarded("seckey_encrypt", [&] { return seckey_encrypt(c, sk, zz); });
+}
+
+long ext_seckey_packed_encrypt(void **c, void *sk, void *zzx)
+{
+    return guarded("seckey_packed_encrypt", [&] { return seckey_packed_encrypt(c, sk, zzx); });
+}
+
+long ext_pubkey_encrypt(void **c, void *pk, void *zz)
+{
+    return guarded("pubkey_encrypt", [&] { return pubkey_encrypt(c, pk, zz); });
+}
+
+long ext_pubkey_packed_encrypt(void **c, void *pk, void *zzx)
+{
+    return guarded("pubkey_packed_encrypt", [&] { return pubkey_packed_encrypt(c, pk, zzx); });
+}
+
+long ext_seckey_decrypt(void **zz, void *sk, void *c)
+{
+    long ret = check_same_context<helib::SecKey, helib::Ctxt>(sk, c);
+    if (ret != kOk)
+        return ret;
+    return guarded("seckey_decrypt", [&] { return seckey_decrypt(zz, sk, c); });
+}
+
+long ext_seckey_packed_decrypt(void **zzx, void *sk, void *c)
+{
+    long ret = check_same_context<helib::SecKey, helib::Ctxt>(sk, c);
+    if (ret != kOk)
+        return ret;
+    return guarded("seckey_packed_decrypt", [&] { return seckey_packed_decrypt(zzx, sk, c); });
+}
+
+long ext_seckey_decrypt_checked(void **zz, void *sk, void *c)
+{
+    long ret = check_noise_budget(c);
+    if (ret != kOk)
+        return ret;
+    return ext_seckey_decrypt(zz, sk, c);
+}
+
+long ext_seckey_packed_decrypt_checked(void **zzx, void *sk, void *c)
+{
+    long ret = check_noise_budget(c);
+    if (ret != kOk)
+        return ret;
+    return ext_seckey_packed_decrypt(zzx, sk, c);
+}
+
+// Ciphertexts
+
+long ext_ctxt_clone(void **c, void *o)
+{
+    return guarded("ctxt_clone", [&] { return ctxt_clone(c, o); });
+}
+
+long ext_ctxt_get_noise_budget(void *c, long *res)
+{
+    return guarded("ctxt_get_noise_budget", [&] { return ctxt_get_noise_budget(c, res); });
+}
+
+long ext_ctxt_add(void **c, void *a, void *b)
+{
+    long ret = check_ctxts(a, b);
+    if (ret != kOk)
+        return ret;
+    return guarded("ctxt_add", [&] { return ctxt_add(c, a, b); });
+}
+
+long ext_ctxt_sub(void **c, void *a, void *b)
+{
+    long ret = check_ctxts(a, b);
+    if (ret != kOk)
+        return ret;
+    return guarded("ctxt_sub", [&] { return ctxt_sub(c, a, b); });
+}
+
+long ext_ctxt_mult(void **c, void *a, void *b)
+{
+    long ret = check_ctxts(a, b);
+    if (ret != kOk)
+        return ret;
+    return guarded("ctxt_mult", [&] { return ctxt_mult(c, a, b); });
+}
+
+long ext_ctxt_negate(void **c, void *a)
+{
+    return guarded("ctxt_negate", [&] { return ctxt_negate(c, a); });
+}
+
+long ext_ctxt_add_inplace(void *a, void *b)
+{
+    long ret = check_ctxts(a, b);
+    if (ret != kOk)
+        return ret;
+    return guarded("ctxt_add_inplace", [&] { return ctxt_add_inplace(a, b); });
+}
+
+long ext_ctxt_sub_inplace(void *a, void *b)
+{
+    long ret = check_ctxts(a, b);
+    if (ret != kOk)
+        return ret;
+    return guarded("ctxt_sub_inplace", [&] { return ctxt_sub_inplace(a, b); });
+}
+
+long ext_ctxt_mult_inplace(void *a, void *b)
+{
+    long ret = check_ctxts(a, b);
+    if (ret != kOk)
+        return ret;
+    return guarded("ctxt_mult_inplace", [&] { return ctxt_mult_inplace(a, b); });
+}
+
+long ext_ctxt_negate_inplace(void *a)
+{
+    return guarded("ctxt_negate_inplace", [&] { return ctxt_negate_inplace(a); });
+}
+
+long ext_ctxt_add_by_constant(void **c, void *a, void *b)
+{
+    return guarded("ctxt_add_by_constant", [&] { return ctxt_add_by_constant(c, a, b); });
+}
+
+long ext_ctxt_sub_by_constant(void **c, void *a, void *b)
+{
+    return guarded("ctxt_sub_by_constant", [&] { return ctxt_sub_by_constant(c, a, b); });
+}
+
+long ext_ctxt_sub_from_constant(void **c, void *a, void *b)
+{
+    return guarded("ctxt_sub_from_constant", [&] { return ctxt_sub_from_constant(c, a, b); });
+}
+
+long ext_ctxt_mult_by_constant(void **c, void *a, void *b)
+{
+    return guarded("ctxt_mult_by_constant", [&] { return ctxt_mult_by_constant(c, a, b); });
+}
+
+long ext_ctxt_add_by_packed_constant(void **c, void *a, void *b)
+{
+    return guarded("ctxt_add_by_packed_constant", [&] { return ctxt_add_by_packed_constant(c, a, b); });
+}
+
+long ext_ctxt_sub_by_packed_constant(void **c, void *a, void *b)
+{
+    return guarded("ctxt_sub_by_packed_constant", [&] { return ctxt_sub_by_packed_constant(c, a, b); });
+}
+
+long ext_ctxt_sub_from_packed_constant(void **c, void *a, void *b)
+{
+    return guarded("ctxt_sub_from_packed_constant", [&] { return ctxt_sub_from_packed_constant(c, a, b); });
+}
+
+long ext_ctxt_mult_by_packed_constant(void **c, void *a, void *b)
+{
+    return guarded("ctxt_mult_by_packed_constant", [&] { return ctxt_mult_by_packed_constant(c, a, b); });
+}
+
+long ext_ctxt_add_by_constant_inplace(void *a, void *b)
+{
+    return guarded("ctxt_add_by_constant_inplace", [&] { return ctxt_add_by_constant_inplace(a, b); });
+}
+
+long ext_ctxt_sub_by_constant_inplace(void *a, void *b)
+{
+    return guarded("ctxt_sub_by_constant_inplace", [&] { return ctxt_sub_by_constant_inplace(a, b); });
+}
+
+long ext_ctxt_sub_from_constant_inplace(void *a, void *b)
+{
+    return guarded("ctxt_sub_from_constant_inplace", [&] { return ctxt_sub_from_constant_inplace(a, b); });
+}
+
+long ext_ctxt_mult_by_constant_inplace(void *a, void *b)
+{
+    return guarded("ctxt_mult_by_constant_inplace", [&] { return ctxt_mult_by_constant_inplace(a, b); });
+}
+
+long ext_ctxt_add_by_packed_constant_inplace(void *a, void *b)
+{
+    return guarded("ctxt_add_by_packed_constant_inplace", [&] { return ctxt_add_by_packed_constant_inplace(a, b); });
+}
+
+long ext_ctxt_sub_by_packed_constant_inplace(void *a, void *b)
+{
+    return guarded("ctxt_sub_by_packed_constant_inplace", [&] { return ctxt_sub_by_packed_constant_inplace(a, b); });
+}
+
+long ext_ctxt_sub_from_packed_constant_inplace(void *a, void *b)
+{
+    return guarded("ctxt_sub_from_packed_constant_inplace", [&] { return ctxt_sub_from_packed_constant_inplace(a, b); });
+}
+
+long ext_ctxt_mult_by_packed_constant_inplace(void *a, void *b)
+{
+    return guarded("ctxt_mult_by_packed_constant_inplace", [&] { return ctxt_mult_by_packed_constant_inplace(a, b); });
+}
+
+// Galois keys
+
+long ext_GK_build(void **gk, long m)
+{
+    return guarded("GK_build", [&] { return GK_build(gk, m); });
+}
+
+long ext_GK_generate_step(void *gk, void *sk, int step)
+{
+    return guarded("GK_generate_step", [&] { return GK_generate_step(gk, sk, step); });
+}
+
+long ext_GK_rotate(void *gk, void *ctxt, int step)
+{
+    return guarded("GK_rotate", [&] { return GK_rotate(gk, ctxt, step); });
+}
+
+// ZZ
+
+long ext_ZZ_from_string(void **zz, const char *s)
+{
+    return guarded("ZZ_from_string", [&] { return ZZ_from_string(zz, s); });
+}
+
+long ext_ZZ_from_long(void **zz, long a)
+{
+    return guarded("ZZ_from_long", [&] { return ZZ_from_long(zz, a); });
+}
+
+long ext_ZZ_from_bytes(void **zz, const uint8_t *buf, long len)
+{
+    return guarded("ZZ_from_bytes", [&] { return ZZ_from_bytes(zz, buf, len); });
+}
+
+long ext_ZZ_bytes(void *zz, long *len)
+{
+    return guarded("ZZ_bytes", [&] { return ZZ_bytes(zz, len); });
+}
+
+long ext_ZZ_to_bytes(void *zz, uint8_t *buf, long len)
+{
+    return guarded("ZZ_to_bytes", [&] { return ZZ_to_bytes(zz, buf, len); });
+}
+
+long ext_ZZ_clone(void **zz, void *other)
+{
+    return guarded("ZZ_clone", [&] { return ZZ_clone(zz, other); });
+}
+
+long ext_ZZ_random(void **zz, void *modulus)
+{
+    return guarded("ZZ_random", [&] { return ZZ_random(zz, modulus); });
+}
+
+// ZZX
+
+long ext_ZZX_from_len(void **zzx, long len)
+{
+    return guarded("ZZX_from_len", [&] { return ZZX_from_len(zzx, len); });
+}
+
+long ext_ZZX_get_length(void *zzx, long *len)
+{
+    return guarded("ZZX_get_length", [&] { return ZZX_get_length(zzx, len); });
+}
+
+long ext_ZZX_get_index(void **zz, void *zzx, long index)
+{
+    return guarded("ZZX_get_index", [&] { return ZZX_get_index(zz, zzx, index); });
+}
+
+long ext_ZZX_set_index(void *zzx, long index, void *zz)
+{
+    return guarded("ZZX_set_index", [&] { return ZZX_set_index(zzx, index, zz); });
+}
@@ -0,0 +1,83 @@
+#pragma once
+
+#include <stdint.h>
+
+// The functions of the HElib C API only return an error code. The ext_ versions
+// call them, catch exceptions escaping from them and report a message naming the
+// failed function, see c_ext_error.h.
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+// Context
+long ext_context_build(void **ctx, long m, void *p, long bits);
+long ext_context_get_security_level(void *ctx, double *res);
+long ext_context_printout(void *ctx);
+
+// Keys
+long ext_seckey_build(void **sk, void *ctx);
+long ext_pubkey_from_seckey(void **pk, void *sk);
+long ext_seckey_encrypt(void **c, void *sk, void *zz);
+long ext_seckey_packed_encrypt(void **c, void *sk, void *zzx);
+long ext_pubkey_encrypt(void **c, void *pk, void *zz);
+long ext_pubkey_packed_encrypt(void **c, void *pk, void *zzx);
+long ext_seckey_decrypt(void **zz, void *sk, void *c);
+long ext_seckey_packed_decrypt(void **zzx, void *sk, void *c);
+
+// Like ext_seckey_decrypt and ext_seckey_packed_decrypt, but fail with
+// 0x80040201 if the noise budget of c is used up
+long ext_seckey_decrypt_checked(void **zz, void *sk, void *c);
+long ext_seckey_packed_decrypt_checked(void **zzx, void *sk, void *c);
+
+// Ciphertexts
+long ext_ctxt_clone(void **c, void *o);
+long ext_ctxt_get_noise_budget(void *c, long *res);
+long ext_ctxt_add(void **c, void *a, void *b);
+long ext_ctxt_sub(void **c, void *a, void *b);
+long ext_ctxt_mult(void **c, void *a, void *b);
+long ext_ctxt_negate(void **c, void *a);
+long ext_ctxt_add_inplace(void *a, void *b);
+long ext_ctxt_sub_inplace(void *a, void *b);
+long ext_ctxt_mult_inplace(void *a, void *b);
+long ext_ctxt_negate_inplace(void *a);
+long ext_ctxt_add_by_constant(void **c, void *a, void *b);
+long ext_ctxt_sub_by_constant(void **c, void *a, void *b);
+long ext_ctxt_sub_from_constant(void **c, void *a, void *b);
+long ext_ctxt_mult_by_constant(void **c, void *a, void *b);
+long ext_ctxt_add_by_packed_constant(void **c, void *a, void *b);
+long ext_ctxt_sub_by_packed_constant(void **c, void *a, void *b);
+long ext_ctxt_sub_from_packed_constant(void **c, void *a, void *b);
+long ext_ctxt_mult_by_packed_constant(void **c, void *a, void *b);
+long ext_ctxt_add_by_constant_inplace(void *a, void *b);
+long ext_ctxt_sub_by_constant_inplace(void *a, void *b);
+long ext_ctxt_sub_from_constant_inplace(void *a, void *b);
+long ext_ctxt_mult_by_constant_inplace(void *a, void *b);
+long ext_ctxt_add_by_packed_constant_inplace(void *a, void *b);
+long ext_ctxt_sub_by_packed_constant_inplace(void *a, void *b);
+long ext_ctxt_sub_from_packed_constant_inplace(void *a, void *b);
+long ext_ctxt_mult_by_packed_constant_inplace(void *a, void *b);
+
+// Galois keys
+long ext_GK_build(void **gk, long m);
+long ext_GK_generate_step(void *gk, void *sk, int step);
+long ext_GK_rotate(void *gk, void *ctxt, int step);
+
+// ZZ
+long ext_ZZ_from_string(void **zz, const char *s);
+long ext_ZZ_from_long(void **zz, long a);
+long ext_ZZ_from_bytes(void **zz, const uint8_t *buf, long len);
+long ext_ZZ_bytes(void *zz, long *len);
+long ext_ZZ_to_bytes(void *zz, uint8_t *buf, long len);
+long ext_ZZ_clone(void **zz, void *other);
+long ext_ZZ_random(void **zz, void *modulus);
+
+// ZZX
+long ext_ZZX_from_len(void **zzx, long len);
+long ext_ZZX_get_length(void *zzx, long *len);
+long ext_ZZX_get_index(void **zz, void *zzx, long index);
+long ext_ZZX_set_index(void *zzx, long index, void *zz);
+
+#ifdef __cplusplus
+}
+#endif
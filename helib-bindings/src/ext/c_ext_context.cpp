@@ -0,0 +1,55 @@
+#include "c_ext_context.h"
+#include "c_ext_utils.h"
+
+#include <helib/helib.h>
+
+using namespace helib_ext;
+
+long context_num_gens(void *context, long *len)
+{
+    helib::Context *ctx = static_cast<helib::Context *>(context);
+    if (ctx == nullptr || len == nullptr)
+        return report_null();
+
+    try
+    {
+        *len = ctx->getZMStar().numOfGens();
+        return kOk;
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
+
+long context_gens(void *context, long *gens, long *ords, long len)
+{
+    helib::Context *ctx = static_cast<helib::Context *>(context);
+    if (ctx == nullptr || ((gens == nullptr || ords == nullptr) && len > 0))
+        return report_null();
+
+    try
+    {
+        const helib::PAlgebra &zMStar = ctx->getZMStar();
+        if (len != zMStar.numOfGens())
+            return report(kInvalidArg, "Length does not match the number of generators");
+        for (long i = 0; i < len; i++)
+        {
+            gens[i] = zMStar.ZmStarGen(i);
+            ords[i] = zMStar.OrderOf(i);
+        }
+        return kOk;
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
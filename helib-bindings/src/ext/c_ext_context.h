@@ -0,0 +1,16 @@
+#pragma once
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+// Number of generators of Z_m^* / <p> which HElib's PAlgebra uses for the slots of context
+long context_num_gens(void *context, long *len);
+
+// Writes the generators of HElib's PAlgebra and the sizes of their hypercube
+// dimensions in HElib's order, len has to match context_num_gens
+long context_gens(void *context, long *gens, long *ords, long len);
+
+#ifdef __cplusplus
+}
+#endif
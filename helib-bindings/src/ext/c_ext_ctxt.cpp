@@ -0,0 +1,75 @@
+#include "c_ext_ctxt.h"
+#include "c_ext_utils.h"
+
+#include <helib/helib.h>
+
+using namespace helib_ext;
+
+long ctxt_bytes(void *ctxt, long *len)
+{
+    helib::Ctxt *c = static_cast<helib::Ctxt *>(ctxt);
+    if (c == nullptr || len == nullptr)
+        return report_null();
+
+    try
+    {
+        *len = serialized_size(*c);
+        return kOk;
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
+
+long ctxt_to_bytes(void *ctxt, uint8_t *buf, long len)
+{
+    helib::Ctxt *c = static_cast<helib::Ctxt *>(ctxt);
+    if (c == nullptr || buf == nullptr)
+        return report_null();
+
+    try
+    {
+        return write_to_buffer(*c, buf, len);
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
+
+long ctxt_from_bytes(void **ctxt, void *pubkey, const uint8_t *buf, long len)
+{
+    helib::PubKey *pk = static_cast<helib::PubKey *>(pubkey);
+    if (ctxt == nullptr || pk == nullptr || buf == nullptr)
+        return report_null();
+
+    helib::Ctxt *c = nullptr;
+    try
+    {
+        ArrayBuf array(const_cast<uint8_t *>(buf), len);
+        std::istream is(&array);
+        c = new helib::Ctxt(*pk);
+        c->read(is);
+        *ctxt = c;
+        return kOk;
+    }
+    catch (const std::exception &e)
+    {
+        delete c;
+        return report(kSerialization, e.what());
+    }
+    catch (...)
+    {
+        delete c;
+        return report(kSerialization, "Invalid binary encoding");
+    }
+}
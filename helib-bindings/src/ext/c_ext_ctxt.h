@@ -0,0 +1,20 @@
+#pragma once
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+// Number of bytes of the HElib binary encoding of ctxt
+long ctxt_bytes(void *ctxt, long *len);
+
+// Writes the HElib binary encoding of ctxt into buf, len has to match ctxt_bytes
+long ctxt_to_bytes(void *ctxt, uint8_t *buf, long len);
+
+// Reads a ciphertext from its HElib binary encoding and binds it to pubkey
+long ctxt_from_bytes(void **ctxt, void *pubkey, const uint8_t *buf, long len);
+
+#ifdef __cplusplus
+}
+#endif
@@ -0,0 +1,75 @@
+#include "c_ext_error.h"
+#include "c_ext_utils.h"
+
+#include <cstring>
+#include <stdexcept>
+#include <string>
+
+using namespace helib_ext;
+
+// Return codes only carry the category of an error. The message is kept per
+// thread until the caller fetches it, similar to errno.
+
+namespace
+{
+thread_local std::string last_error;
+} // namespace
+
+namespace helib_ext
+{
+long report(long code, const char *what)
+{
+    last_error = what != nullptr ? what : "";
+    return code;
+}
+
+long report_exception(const std::exception &e)
+{
+    // helib::InvalidArgument derives from std::invalid_argument
+    if (dynamic_cast<const std::invalid_argument *>(&e) != nullptr)
+        return report(kInvalidArg, e.what());
+    // HElib asserts matching contexts in its binary operations with this message
+    if (std::strstr(e.what(), "Context mismatch") != nullptr)
+        return report(kContextMismatch, e.what());
+    return report(kException, e.what());
+}
+
+long report_null()
+{
+    return report(kPointer, "Null pointer");
+}
+
+long report_unknown()
+{
+    return report(kUnexpected, "Unknown exception");
+}
+} // namespace helib_ext
+
+// The accessors never report, so their failures do not replace the message
+// they are asked for. They drop it instead, such that it cannot be attributed
+// to a later call.
+
+long ext_last_error_bytes(long *len)
+{
+    if (len == nullptr)
+    {
+        last_error.clear();
+        return kPointer;
+    }
+
+    *len = static_cast<long>(last_error.size());
+    return kOk;
+}
+
+long ext_last_error(uint8_t *buf, long len)
+{
+    if (buf == nullptr || len != static_cast<long>(last_error.size()))
+    {
+        last_error.clear();
+        return buf == nullptr ? kPointer : kInvalidArg;
+    }
+
+    std::memcpy(buf, last_error.data(), last_error.size());
+    last_error.clear();
+    return kOk;
+}
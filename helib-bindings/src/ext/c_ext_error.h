@@ -0,0 +1,29 @@
+#pragma once
+
+#include <stdint.h>
+
+// Every function of the extension layer returns 0 on success. Any other return
+// value is an error category and the failing call stores a message, which is
+// kept per thread until it is fetched with ext_last_error:
+//   0x80004003 null pointer argument
+//   0x80070057 invalid argument, also std::invalid_argument thrown by HElib
+//   0x8000FFFF exception not derived from std::exception
+//   0x80040201 noise budget of a ciphertext exhausted
+//   0x80040202 objects belong to different contexts
+//   0x80040203 other exception thrown by HElib or NTL
+//   0x80040204 invalid binary encoding
+//   0x80040205 integer out of the range of the requested representation
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+// Number of bytes of the message of the last failed call of the calling thread, 0 if there is none
+long ext_last_error_bytes(long *len);
+
+// Copies the message of the last failed call of the calling thread into buf and clears it, len has to match ext_last_error_bytes
+long ext_last_error(uint8_t *buf, long len);
+
+#ifdef __cplusplus
+}
+#endif
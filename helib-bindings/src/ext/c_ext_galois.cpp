@@ -0,0 +1,293 @@
+#include "c_ext_galois.h"
+#include "c_ext_access.h"
+#include "c_ext_utils.h"
+
+#include <helib/GaloisKey2k.h>
+#include <helib/helib.h>
+
+#include <map>
+#include <memory>
+
+using namespace helib_ext;
+
+// GaloisKey2k stores one helib::KeySwitch per rotation step, which are
+// (de)serialized with the regular HElib writeTo/readFrom functions.
+
+long GK_step_bytes(void *gk, int step, long *len)
+{
+    helib::GaloisKey2k *g = static_cast<helib::GaloisKey2k *>(gk);
+    if (g == nullptr || len == nullptr)
+        return report_null();
+
+    try
+    {
+        if (!g->hasKey(step))
+            return report(kInvalidArg, "No Galois key for the step");
+        *len = serialized_size(g->getKey(step));
+        return kOk;
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
+
+long GK_step_to_bytes(void *gk, int step, uint8_t *buf, long len)
+{
+    helib::GaloisKey2k *g = static_cast<helib::GaloisKey2k *>(gk);
+    if (g == nullptr || buf == nullptr)
+        return report_null();
+
+    try
+    {
+        if (!g->hasKey(step))
+            return report(kInvalidArg, "No Galois key for the step");
+        return write_to_buffer(g->getKey(step), buf, len);
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
+
+long GK_step_from_bytes(void *gk, void *context, int step, const uint8_t *buf, long len)
+{
+    helib::GaloisKey2k *g = static_cast<helib::GaloisKey2k *>(gk);
+    helib::Context *ctx = static_cast<helib::Context *>(context);
+    if (g == nullptr || ctx == nullptr || buf == nullptr)
+        return report_null();
+
+    try
+    {
+        ArrayBuf array(const_cast<uint8_t *>(buf), len);
+        std::istream is(&array);
+        g->setKey(step, helib::KeySwitch::readFrom(is, *ctx));
+        return kOk;
+    }
+    catch (const std::exception &e)
+    {
+        return report(kSerialization, e.what());
+    }
+    catch (...)
+    {
+        return report(kSerialization, "Invalid binary encoding");
+    }
+}
+
+// Hoisting: the expensive part of a key-switch is breaking the ciphertext into
+// digits. Automorphisms commute with this decomposition, so the digits of one
+// ciphertext can be computed once and reused for every rotation. Same steps as
+// helib::BasicAutomorphPrecon, but with the matrices of the GaloisKey2k instead
+// of the ones of the public key. Assembling the result needs the internals of
+// helib::Ctxt, which are reached through helib::ExtAccess. The AK_* functions
+// below reuse the same steps for their automorphisms.
+
+namespace
+{
+struct HoistedCtxt
+{
+    helib::Ctxt ctxt;
+    std::vector<helib::DoubleCRT> digits;
+    NTL::xdouble noise;
+};
+
+std::unique_ptr<HoistedCtxt> hoist(const helib::Ctxt &c)
+{
+    std::unique_ptr<HoistedCtxt> h(new HoistedCtxt{c, {}, NTL::xdouble(0.0)});
+    // Canonical form (c0, c1) w.r.t. the secret key
+    h->ctxt.cleanUp();
+    const helib::Context &context = h->ctxt.getContext();
+    h->noise = h->ctxt.getNoiseBound() * NTL::xexp(context.logOfProduct(context.getSpecialPrimes()));
+    const helib::Ctxt &canonical = h->ctxt;
+    if (canonical.size() > 1)
+        canonical[1].breakIntoDigits(h->digits);
+    return h;
+}
+
+// Applies the automorphism W switches from to the hoisted ciphertext
+std::unique_ptr<helib::Ctxt> automorph_hoisted(const HoistedCtxt &h, const helib::KeySwitch &W)
+{
+    long k = W.fromKey.getPowerOfX();
+    std::unique_ptr<helib::Ctxt> r;
+    if (h.digits.empty())
+    {
+        // Only the constant part, no key-switch required
+        r.reset(new helib::Ctxt(h.ctxt));
+        r->automorph(k);
+        return r;
+    }
+
+    const helib::Ctxt &canonical = h.ctxt;
+    const helib::Context &context = canonical.getContext();
+    r.reset(new helib::Ctxt(helib::ZeroCtxtLike, canonical));
+    helib::ExtAccess::set_noise_bound(*r, h.noise);
+
+    helib::DoubleCRT c0 = canonical[0];
+    c0.automorph(k);
+    c0.addPrimesAndScale(context.getSpecialPrimes());
+    helib::ExtAccess::add_part(*r, c0, helib::SKHandle(0, 1, 0));
+
+    std::vector<helib::DoubleCRT> digits = h.digits;
+    for (helib::DoubleCRT &digit : digits)
+        digit.automorph(k);
+    helib::ExtAccess::key_switch_digits(*r, W, digits);
+    r->modDownToSet(canonical.getPrimeSet());
+    return r;
+}
+
+struct AutomorphKeys
+{
+    std::map<long, helib::KeySwitch> keys;
+};
+} // namespace
+
+long GK_hoist(void **hoisted, void *ctxt)
+{
+    helib::Ctxt *c = static_cast<helib::Ctxt *>(ctxt);
+    if (hoisted == nullptr || c == nullptr)
+        return report_null();
+
+    try
+    {
+        *hoisted = hoist(*c).release();
+        return kOk;
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
+
+long GK_hoisted_rotate(void **ctxt, void *gk, void *hoisted, int step)
+{
+    helib::GaloisKey2k *g = static_cast<helib::GaloisKey2k *>(gk);
+    HoistedCtxt *h = static_cast<HoistedCtxt *>(hoisted);
+    if (ctxt == nullptr || g == nullptr || h == nullptr)
+        return report_null();
+
+    try
+    {
+        if (!g->hasKey(step))
+            return report(kInvalidArg, "No Galois key for the step");
+        *ctxt = automorph_hoisted(*h, g->getKey(step)).release();
+        return kOk;
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
+
+long GK_hoisted_destroy(void *hoisted)
+{
+    HoistedCtxt *h = static_cast<HoistedCtxt *>(hoisted);
+    if (h == nullptr)
+        return report_null();
+
+    delete h;
+    return kOk;
+}
+
+// The matrices are generated with SecKey::GenKeySWmatrix on a copy of the
+// secret key, such that the key itself is not modified. Applying one is a
+// hoisted rotation with a single automorphism.
+
+long AK_build(void **ak)
+{
+    if (ak == nullptr)
+        return report_null();
+
+    try
+    {
+        *ak = new AutomorphKeys;
+        return kOk;
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
+
+long AK_destroy(void *ak)
+{
+    AutomorphKeys *a = static_cast<AutomorphKeys *>(ak);
+    if (a == nullptr)
+        return report_null();
+
+    delete a;
+    return kOk;
+}
+
+long AK_generate(void *ak, void *seckey, long k)
+{
+    AutomorphKeys *a = static_cast<AutomorphKeys *>(ak);
+    helib::SecKey *sk = static_cast<helib::SecKey *>(seckey);
+    if (a == nullptr || sk == nullptr)
+        return report_null();
+
+    try
+    {
+        long m = sk->getContext().getM();
+        if (k <= 0 || k >= m || NTL::GCD(k, m) != 1)
+            return report(kInvalidArg, "The automorphism is not a unit mod m");
+
+        helib::SecKey scratch(*sk);
+        scratch.GenKeySWmatrix(1, k, 0, 0);
+        a->keys.erase(k);
+        a->keys.emplace(k, scratch.getKeySWmatrix(helib::SKHandle(1, k, 0), 0));
+        return kOk;
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
+
+long AK_automorph(void *ak, void *ctxt, long k)
+{
+    AutomorphKeys *a = static_cast<AutomorphKeys *>(ak);
+    helib::Ctxt *c = static_cast<helib::Ctxt *>(ctxt);
+    if (a == nullptr || c == nullptr)
+        return report_null();
+
+    try
+    {
+        auto key = a->keys.find(k);
+        if (key == a->keys.end())
+            return report(kInvalidArg, "No key for the automorphism");
+        *c = *automorph_hoisted(*hoist(*c), key->second);
+        return kOk;
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
@@ -0,0 +1,40 @@
+#pragma once
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+// Number of bytes of the binary encoding of the key-switching matrix for step
+long GK_step_bytes(void *gk, int step, long *len);
+
+// Writes the binary encoding of the key-switching matrix for step into buf, len has to match GK_step_bytes
+long GK_step_to_bytes(void *gk, int step, uint8_t *buf, long len);
+
+// Reads a key-switching matrix for step belonging to context and adds it to gk
+long GK_step_from_bytes(void *gk, void *context, int step, const uint8_t *buf, long len);
+
+// Decomposes ctxt once, such that it can be rotated by several steps of gk without decomposing it again
+long GK_hoist(void **hoisted, void *ctxt);
+
+// Rotates the hoisted ciphertext by step, which needs a key for exactly this step
+long GK_hoisted_rotate(void **ctxt, void *gk, void *hoisted, int step);
+
+long GK_hoisted_destroy(void *hoisted);
+
+// Key-switching matrices for automorphisms X -> X^k with any k coprime to m, for
+// cyclotomics other than powers of two
+long AK_build(void **ak);
+
+long AK_destroy(void *ak);
+
+// Generates the key-switching matrix for X -> X^k from seckey and adds it to ak
+long AK_generate(void *ak, void *seckey, long k);
+
+// Applies X -> X^k to ctxt in place, which needs a matrix for exactly this k
+long AK_automorph(void *ak, void *ctxt, long k);
+
+#ifdef __cplusplus
+}
+#endif
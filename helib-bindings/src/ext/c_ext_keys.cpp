@@ -0,0 +1,185 @@
+#include "c_ext_keys.h"
+#include "c_ext_utils.h"
+
+#include <helib/helib.h>
+
+#include <NTL/ZZX.h>
+
+using namespace helib_ext;
+
+long seckey_bytes(void *seckey, long *len)
+{
+    helib::SecKey *sk = static_cast<helib::SecKey *>(seckey);
+    if (sk == nullptr || len == nullptr)
+        return report_null();
+
+    try
+    {
+        *len = serialized_size(*sk);
+        return kOk;
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
+
+long seckey_to_bytes(void *seckey, uint8_t *buf, long len)
+{
+    helib::SecKey *sk = static_cast<helib::SecKey *>(seckey);
+    if (sk == nullptr || buf == nullptr)
+        return report_null();
+
+    try
+    {
+        // Writes directly into buf, no temporary copy of the key is made
+        return write_to_buffer(*sk, buf, len);
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
+
+long seckey_from_bytes(void **seckey, void *context, const uint8_t *buf, long len)
+{
+    helib::Context *ctx = static_cast<helib::Context *>(context);
+    if (seckey == nullptr || ctx == nullptr || buf == nullptr)
+        return report_null();
+
+    try
+    {
+        ArrayBuf array(const_cast<uint8_t *>(buf), len);
+        std::istream is(&array);
+        *seckey = new helib::SecKey(helib::SecKey::readFrom(is, *ctx));
+        return kOk;
+    }
+    catch (const std::exception &e)
+    {
+        return report(kSerialization, e.what());
+    }
+    catch (...)
+    {
+        return report(kSerialization, "Invalid binary encoding");
+    }
+}
+
+long seckey_packed_decrypt_to_limbs(void *seckey, void *ctxt, uint64_t *limbs, long len, long limbs_per_coeff)
+{
+    helib::SecKey *sk = static_cast<helib::SecKey *>(seckey);
+    helib::Ctxt *c = static_cast<helib::Ctxt *>(ctxt);
+    if (sk == nullptr || c == nullptr || (limbs == nullptr && len > 0))
+        return report_null();
+    if (len < 0 || limbs_per_coeff <= 0)
+        return report(kInvalidArg, "Invalid number of coefficients or limbs");
+    if (&sk->getContext() != &c->getContext())
+        return report(kContextMismatch, "The objects belong to different contexts");
+
+    try
+    {
+        NTL::ZZX poly;
+        sk->Decrypt(poly, *c);
+        return zzx_to_limbs(limbs, poly, len, limbs_per_coeff);
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
+
+long pubkey_bytes(void *pubkey, long *len)
+{
+    helib::PubKey *pk = static_cast<helib::PubKey *>(pubkey);
+    if (pk == nullptr || len == nullptr)
+        return report_null();
+
+    try
+    {
+        *len = serialized_size(*pk);
+        return kOk;
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
+
+long pubkey_to_bytes(void *pubkey, uint8_t *buf, long len)
+{
+    helib::PubKey *pk = static_cast<helib::PubKey *>(pubkey);
+    if (pk == nullptr || buf == nullptr)
+        return report_null();
+
+    try
+    {
+        return write_to_buffer(*pk, buf, len);
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
+
+long pubkey_from_bytes(void **pubkey, void *context, const uint8_t *buf, long len)
+{
+    helib::Context *ctx = static_cast<helib::Context *>(context);
+    if (pubkey == nullptr || ctx == nullptr || buf == nullptr)
+        return report_null();
+
+    try
+    {
+        ArrayBuf array(const_cast<uint8_t *>(buf), len);
+        std::istream is(&array);
+        *pubkey = new helib::PubKey(helib::PubKey::readFrom(is, *ctx));
+        return kOk;
+    }
+    catch (const std::exception &e)
+    {
+        return report(kSerialization, e.what());
+    }
+    catch (...)
+    {
+        return report(kSerialization, "Invalid binary encoding");
+    }
+}
+
+long pubkey_empty(void **pubkey, void *context)
+{
+    helib::Context *ctx = static_cast<helib::Context *>(context);
+    if (pubkey == nullptr || ctx == nullptr)
+        return report_null();
+
+    try
+    {
+        *pubkey = new helib::PubKey(*ctx);
+        return kOk;
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
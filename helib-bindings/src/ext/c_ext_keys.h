@@ -0,0 +1,39 @@
+#pragma once
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+// Number of bytes of the HElib binary encoding of seckey
+long seckey_bytes(void *seckey, long *len);
+
+// Writes the HElib binary encoding of seckey into buf, len has to match seckey_bytes
+long seckey_to_bytes(void *seckey, uint8_t *buf, long len);
+
+// Reads a secret key belonging to context from its HElib binary encoding
+long seckey_from_bytes(void **seckey, void *context, const uint8_t *buf, long len);
+
+// Number of bytes of the HElib binary encoding of pubkey
+long pubkey_bytes(void *pubkey, long *len);
+
+// Writes the HElib binary encoding of pubkey into buf, len has to match pubkey_bytes
+long pubkey_to_bytes(void *pubkey, uint8_t *buf, long len);
+
+// Reads a public key belonging to context from its HElib binary encoding
+long pubkey_from_bytes(void **pubkey, void *context, const uint8_t *buf, long len);
+
+// Decrypts ctxt with seckey and writes the first len coefficients of the plaintext
+// polynomial into limbs in the layout of ZZX_from_limbs. No plaintext object is
+// handed out, the polynomial only lives inside this call. Fails with a context
+// mismatch error if ctxt belongs to a different context.
+long seckey_packed_decrypt_to_limbs(void *seckey, void *ctxt, uint64_t *limbs, long len, long limbs_per_coeff);
+
+// Public key of context without any key material. Ciphertexts bound to it can
+// be loaded, added and serialized, but not relinearized or key-switched.
+long pubkey_empty(void **pubkey, void *context);
+
+#ifdef __cplusplus
+}
+#endif
@@ -0,0 +1,28 @@
+#include "c_ext_rng.h"
+#include "c_ext_utils.h"
+
+#include <NTL/ZZ.h>
+
+using namespace helib_ext;
+
+long ext_set_thread_seed(const uint8_t *seed, long len)
+{
+    if (seed == nullptr)
+        return report_null();
+    if (len <= 0)
+        return report(kInvalidArg, "Empty seed");
+
+    try
+    {
+        NTL::SetSeed(seed, len);
+        return kOk;
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
@@ -0,0 +1,14 @@
+#pragma once
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+// Seeds the NTL random stream of the calling thread, from which HElib draws key generation and encryption randomness
+long ext_set_thread_seed(const uint8_t *seed, long len);
+
+#ifdef __cplusplus
+}
+#endif
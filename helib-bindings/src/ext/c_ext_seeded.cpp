@@ -0,0 +1,184 @@
+#include "c_ext_seeded.h"
+#include "c_ext_access.h"
+#include "c_ext_utils.h"
+
+#include <helib/binio.h>
+#include <helib/helib.h>
+
+#include <memory>
+
+using namespace helib_ext;
+
+namespace
+{
+// The b-part of a secret-key encryption together with the noise bound of the encryption
+struct SeededCtxt
+{
+    helib::DoubleCRT b;
+    NTL::xdouble noise;
+
+    void writeTo(std::ostream &os) const
+    {
+        b.writeTo(os);
+        helib::write_raw_xdouble(os, noise);
+    }
+};
+
+// The a-part of a seeded ciphertext, uniformly random over the primes of primeSet
+helib::DoubleCRT expand_seed(const helib::Context &context, const helib::IndexSet &primeSet,
+                             const uint8_t *seed, long seed_len)
+{
+    helib::DoubleCRT a(context, primeSet);
+    NTL::ZZ s = NTL::ZZFromBytes(seed, seed_len);
+    a.randomize(&s);
+    return a;
+}
+} // namespace
+
+long seeded_ctxt_encrypt(void **seeded, void *seckey, void *ptxt, const uint8_t *seed, long seed_len)
+{
+    helib::SecKey *sk = static_cast<helib::SecKey *>(seckey);
+    NTL::ZZX *p = static_cast<NTL::ZZX *>(ptxt);
+    if (seeded == nullptr || sk == nullptr || p == nullptr || seed == nullptr)
+        return report_null();
+    if (seed_len <= 0)
+        return report(kInvalidArg, "Empty seed");
+
+    try
+    {
+        // Fresh encryption (b, a) with b = -a*s + p*e + m. Replacing a by the
+        // seeded a' and b by b + (a - a')*s keeps the noise untouched.
+        helib::Ctxt c(*sk);
+        sk->Encrypt(c, *p);
+        const helib::Ctxt &enc = c;
+        helib::DoubleCRT a = expand_seed(sk->getContext(), enc.getPrimeSet(), seed, seed_len);
+
+        helib::DoubleCRT diff = enc[1];
+        diff -= a;
+        diff *= helib::ExtAccess::secret_key(*sk, 0);
+
+        std::unique_ptr<SeededCtxt> s(new SeededCtxt{enc[0], enc.getNoiseBound()});
+        s->b += diff;
+        *seeded = s.release();
+        return kOk;
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
+
+long seeded_ctxt_destroy(void *seeded)
+{
+    SeededCtxt *s = static_cast<SeededCtxt *>(seeded);
+    if (s == nullptr)
+        return report_null();
+
+    delete s;
+    return kOk;
+}
+
+long seeded_ctxt_bytes(void *seeded, long *len)
+{
+    SeededCtxt *s = static_cast<SeededCtxt *>(seeded);
+    if (s == nullptr || len == nullptr)
+        return report_null();
+
+    try
+    {
+        *len = serialized_size(*s);
+        return kOk;
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
+
+long seeded_ctxt_to_bytes(void *seeded, uint8_t *buf, long len)
+{
+    SeededCtxt *s = static_cast<SeededCtxt *>(seeded);
+    if (s == nullptr || buf == nullptr)
+        return report_null();
+
+    try
+    {
+        return write_to_buffer(*s, buf, len);
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
+
+long seeded_ctxt_from_bytes(void **seeded, void *context, const uint8_t *buf, long len)
+{
+    helib::Context *ctx = static_cast<helib::Context *>(context);
+    if (seeded == nullptr || ctx == nullptr || buf == nullptr)
+        return report_null();
+
+    try
+    {
+        ArrayBuf array(const_cast<uint8_t *>(buf), len);
+        std::istream is(&array);
+        helib::DoubleCRT b = helib::DoubleCRT::readFrom(is, *ctx);
+        NTL::xdouble noise = helib::read_raw_xdouble(is);
+        if (!is)
+            return report(kSerialization, "Buffer too short");
+        *seeded = new SeededCtxt{b, noise};
+        return kOk;
+    }
+    catch (const std::exception &e)
+    {
+        return report(kSerialization, e.what());
+    }
+    catch (...)
+    {
+        return report(kSerialization, "Invalid binary encoding");
+    }
+}
+
+long seeded_ctxt_expand(void **ctxt, void *seeded, void *pubkey, const uint8_t *seed, long seed_len)
+{
+    SeededCtxt *s = static_cast<SeededCtxt *>(seeded);
+    helib::PubKey *pk = static_cast<helib::PubKey *>(pubkey);
+    if (ctxt == nullptr || s == nullptr || pk == nullptr || seed == nullptr)
+        return report_null();
+    if (seed_len <= 0)
+        return report(kInvalidArg, "Empty seed");
+
+    try
+    {
+        // (b, a) with the a-part rebuilt from the seed, which carries the noise
+        // bound of the secret-key encryption
+        const helib::Context &context = pk->getContext();
+        if (s->b.getIndexSet() != context.getCtxtPrimes())
+            return report(kContextMismatch, "Seeded ciphertext and public key use different primes");
+
+        std::unique_ptr<helib::Ctxt> c(new helib::Ctxt(*pk));
+        helib::DoubleCRT a = expand_seed(context, s->b.getIndexSet(), seed, seed_len);
+        helib::ExtAccess::set_parts(*c, s->b, a, s->noise);
+        *ctxt = c.release();
+        return kOk;
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
@@ -0,0 +1,32 @@
+#pragma once
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+// Secret-key encryption of the packed plaintext ptxt (a ZZX), where the random
+// a-part of the ciphertext is derived from seed. Only the b-part (a DoubleCRT)
+// and the noise bound of the encryption are returned in seeded, the a-part can
+// be rebuilt from the seed.
+long seeded_ctxt_encrypt(void **seeded, void *seckey, void *ptxt, const uint8_t *seed, long seed_len);
+
+long seeded_ctxt_destroy(void *seeded);
+
+// Number of bytes of the HElib binary encoding of the b-part, followed by the noise bound
+long seeded_ctxt_bytes(void *seeded, long *len);
+
+// Writes the binary encoding of seeded into buf, len has to match seeded_ctxt_bytes
+long seeded_ctxt_to_bytes(void *seeded, uint8_t *buf, long len);
+
+// Reads a seeded ciphertext belonging to context from its binary encoding
+long seeded_ctxt_from_bytes(void **seeded, void *context, const uint8_t *buf, long len);
+
+// Rebuilds the full ciphertext under pubkey from the b-part and the seed, without
+// encrypting. It keeps the noise bound of the secret-key encryption.
+long seeded_ctxt_expand(void **ctxt, void *seeded, void *pubkey, const uint8_t *seed, long seed_len);
+
+#ifdef __cplusplus
+}
+#endif
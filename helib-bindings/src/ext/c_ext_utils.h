@@ -0,0 +1,115 @@
+#pragma once
+
+// Shared helpers for the C extension layer. Not part of the C interface
+// itself, so this header is only included from the .cpp files.
+
+#include <cstdint>
+#include <exception>
+#include <istream>
+#include <ostream>
+#include <streambuf>
+
+namespace NTL
+{
+class ZZ;
+class ZZX;
+}
+
+namespace helib_ext
+{
+constexpr long kOk = 0L;
+constexpr long kPointer = 0x80004003L;
+constexpr long kInvalidArg = 0x80070057L;
+constexpr long kUnexpected = 0x8000FFFFL;
+// Error categories beyond the generic HRESULT codes, see c_ext_error.h
+constexpr long kNoiseBudget = 0x80040201L;
+constexpr long kContextMismatch = 0x80040202L;
+constexpr long kException = 0x80040203L;
+constexpr long kSerialization = 0x80040204L;
+constexpr long kOutOfRange = 0x80040205L;
+
+// Stores what as the message of the last error of the calling thread and returns code
+long report(long code, const char *what);
+
+// Category and message of an exception thrown by HElib or NTL
+long report_exception(const std::exception &e);
+
+// kPointer for a null argument
+long report_null();
+
+// kUnexpected for an exception not derived from std::exception
+long report_unknown();
+
+// Sets x to the non-negative integer given by n 64-bit limbs, least significant
+// first. Implemented in c_ext_zz.cpp.
+void zz_from_limbs(NTL::ZZ &x, const uint64_t *limbs, long n);
+
+// Writes x as n 64-bit limbs, least significant first. Fails with kOutOfRange if
+// x is negative or does not fit into n limbs. Implemented in c_ext_zz.cpp.
+long zz_to_limbs(uint64_t *limbs, const NTL::ZZ &x, long n);
+
+// Writes the first len coefficients of poly as limbs_per_coeff limbs each, see
+// ZZX_to_limbs. Implemented in c_ext_zzx.cpp.
+long zzx_to_limbs(uint64_t *limbs, const NTL::ZZX &poly, long len, long limbs_per_coeff);
+
+// Stream buffer which only counts the written bytes
+class CountingBuf : public std::streambuf
+{
+public:
+    long count() const { return count_; }
+
+protected:
+    std::streamsize xsputn(const char *, std::streamsize n) override
+    {
+        count_ += n;
+        return n;
+    }
+
+    int_type overflow(int_type ch) override
+    {
+        if (!traits_type::eq_int_type(ch, traits_type::eof()))
+            count_++;
+        return traits_type::not_eof(ch);
+    }
+
+private:
+    long count_ = 0;
+};
+
+// Stream buffer over a fixed, caller owned memory region. Reading and writing
+// happens in place, so no (possibly secret) copies are left behind on the heap.
+class ArrayBuf : public std::streambuf
+{
+public:
+    ArrayBuf(uint8_t *buf, long len)
+    {
+        char *begin = reinterpret_cast<char *>(buf);
+        setp(begin, begin + len);
+        setg(begin, begin, begin + len);
+    }
+
+    long written() const { return static_cast<long>(pptr() - pbase()); }
+};
+
+// Number of bytes obj produces with its HElib writeTo function
+template <typename T>
+long serialized_size(const T &obj)
+{
+    CountingBuf counter;
+    std::ostream os(&counter);
+    obj.writeTo(os);
+    return counter.count();
+}
+
+// Writes obj with its HElib writeTo function into a buffer of exactly len bytes
+template <typename T>
+long write_to_buffer(const T &obj, uint8_t *buf, long len)
+{
+    ArrayBuf array(buf, len);
+    std::ostream os(&array);
+    obj.writeTo(os);
+    if (!os || array.written() != len)
+        return report(kInvalidArg, "Buffer length does not match the serialized size");
+    return kOk;
+}
+} // namespace helib_ext
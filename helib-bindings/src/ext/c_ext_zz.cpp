@@ -0,0 +1,195 @@
+#include "c_ext_zz.h"
+#include "c_ext_utils.h"
+
+#include <NTL/ZZ.h>
+
+#include <vector>
+
+using namespace helib_ext;
+
+namespace
+{
+constexpr long kLimbBytes = 8;
+
+// Allocates the result of op applied to the operands into *res
+template <typename Op>
+long binary_op(void **res, void *a, void *b, Op op)
+{
+    if (res == nullptr || a == nullptr || b == nullptr)
+        return report_null();
+
+    try
+    {
+        NTL::ZZ *x = new NTL::ZZ;
+        op(*x, *static_cast<NTL::ZZ *>(a), *static_cast<NTL::ZZ *>(b));
+        *res = x;
+        return kOk;
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
+} // namespace
+
+// The limbs are converted byte by byte, such that the layout does not depend on
+// the endianness of the host
+void helib_ext::zz_from_limbs(NTL::ZZ &x, const uint64_t *limbs, long n)
+{
+    std::vector<unsigned char> bytes(n * kLimbBytes);
+    for (long i = 0; i < n; i++)
+        for (long j = 0; j < kLimbBytes; j++)
+            bytes[i * kLimbBytes + j] = static_cast<unsigned char>(limbs[i] >> (8 * j));
+    NTL::ZZFromBytes(x, bytes.data(), bytes.size());
+}
+
+long helib_ext::zz_to_limbs(uint64_t *limbs, const NTL::ZZ &x, long n)
+{
+    if (NTL::sign(x) < 0)
+        return report(kOutOfRange, "Negative integer");
+    if (NTL::NumBytes(x) > n * kLimbBytes)
+        return report(kOutOfRange, "Integer does not fit into the limbs");
+
+    std::vector<unsigned char> bytes(n * kLimbBytes);
+    NTL::BytesFromZZ(bytes.data(), x, bytes.size());
+    for (long i = 0; i < n; i++)
+    {
+        uint64_t limb = 0;
+        for (long j = 0; j < kLimbBytes; j++)
+            limb |= static_cast<uint64_t>(bytes[i * kLimbBytes + j]) << (8 * j);
+        limbs[i] = limb;
+    }
+    return kOk;
+}
+
+long ZZ_from_limbs(void **zz, const uint64_t *limbs, long n)
+{
+    if (zz == nullptr || (limbs == nullptr && n > 0))
+        return report_null();
+    if (n < 0)
+        return report(kInvalidArg, "Negative number of limbs");
+
+    try
+    {
+        NTL::ZZ *x = new NTL::ZZ;
+        zz_from_limbs(*x, limbs, n);
+        *zz = x;
+        return kOk;
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
+
+long ZZ_to_limbs(void *zz, uint64_t *limbs, long n)
+{
+    if (zz == nullptr || (limbs == nullptr && n > 0))
+        return report_null();
+    if (n < 0)
+        return report(kInvalidArg, "Negative number of limbs");
+
+    try
+    {
+        return zz_to_limbs(limbs, *static_cast<NTL::ZZ *>(zz), n);
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
+
+long ZZ_add(void **res, void *a, void *b)
+{
+    return binary_op(res, a, b, [](NTL::ZZ &x, const NTL::ZZ &a, const NTL::ZZ &b)
+                     { NTL::add(x, a, b); });
+}
+
+long ZZ_sub(void **res, void *a, void *b)
+{
+    return binary_op(res, a, b, [](NTL::ZZ &x, const NTL::ZZ &a, const NTL::ZZ &b)
+                     { NTL::sub(x, a, b); });
+}
+
+long ZZ_mul(void **res, void *a, void *b)
+{
+    return binary_op(res, a, b, [](NTL::ZZ &x, const NTL::ZZ &a, const NTL::ZZ &b)
+                     { NTL::mul(x, a, b); });
+}
+
+long ZZ_rem(void **res, void *a, void *b)
+{
+    if (b != nullptr && NTL::IsZero(*static_cast<NTL::ZZ *>(b)))
+        return report(kInvalidArg, "Division by zero");
+    return binary_op(res, a, b, [](NTL::ZZ &x, const NTL::ZZ &a, const NTL::ZZ &b)
+                     { NTL::rem(x, a, b); });
+}
+
+long ZZ_powmod(void **res, void *base, void *exp, void *modulus)
+{
+    if (res == nullptr || base == nullptr || exp == nullptr || modulus == nullptr)
+        return report_null();
+
+    const NTL::ZZ &e = *static_cast<NTL::ZZ *>(exp);
+    const NTL::ZZ &n = *static_cast<NTL::ZZ *>(modulus);
+    if (NTL::sign(e) < 0)
+        return report(kInvalidArg, "Negative exponent");
+    if (NTL::sign(n) <= 0)
+        return report(kInvalidArg, "Modulus is not positive");
+
+    try
+    {
+        NTL::ZZ *x = new NTL::ZZ;
+        if (NTL::IsOne(n))
+        {
+            NTL::clear(*x);
+        }
+        else
+        {
+            // PowerMod expects the base in [0, modulus)
+            NTL::ZZ b;
+            NTL::rem(b, *static_cast<NTL::ZZ *>(base), n);
+            NTL::PowerMod(*x, b, e, n);
+        }
+        *res = x;
+        return kOk;
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
+
+long ZZ_compare(void *a, void *b, long *cmp)
+{
+    if (a == nullptr || b == nullptr || cmp == nullptr)
+        return report_null();
+
+    *cmp = NTL::compare(*static_cast<NTL::ZZ *>(a), *static_cast<NTL::ZZ *>(b));
+    return kOk;
+}
+
+long ZZ_sign(void *zz, long *sign)
+{
+    if (zz == nullptr || sign == nullptr)
+        return report_null();
+
+    *sign = NTL::sign(*static_cast<NTL::ZZ *>(zz));
+    return kOk;
+}
@@ -0,0 +1,41 @@
+#pragma once
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+// Creates a ZZ from n 64-bit limbs, least significant first
+long ZZ_from_limbs(void **zz, const uint64_t *limbs, long n);
+
+// Writes zz as n 64-bit limbs, least significant first. Fails with an out of
+// range error if zz is negative or does not fit into n limbs.
+long ZZ_to_limbs(void *zz, uint64_t *limbs, long n);
+
+// res = a + b
+long ZZ_add(void **res, void *a, void *b);
+
+// res = a - b
+long ZZ_sub(void **res, void *a, void *b);
+
+// res = a * b
+long ZZ_mul(void **res, void *a, void *b);
+
+// res = a mod b, with the sign of b (NTL's rem). Fails with an invalid argument
+// error if b is zero.
+long ZZ_rem(void **res, void *a, void *b);
+
+// res = base^exp mod modulus in [0, modulus). Fails with an invalid argument
+// error if exp is negative or modulus is not positive.
+long ZZ_powmod(void **res, void *base, void *exp, void *modulus);
+
+// Sets cmp to -1, 0 or 1 if a is smaller than, equal to or larger than b
+long ZZ_compare(void *a, void *b, long *cmp);
+
+// Sets sign to -1, 0 or 1 if zz is negative, zero or positive
+long ZZ_sign(void *zz, long *sign);
+
+#ifdef __cplusplus
+}
+#endif
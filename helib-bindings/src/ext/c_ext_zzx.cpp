@@ -0,0 +1,68 @@
+#include "c_ext_zzx.h"
+#include "c_ext_utils.h"
+
+#include <NTL/ZZX.h>
+
+using namespace helib_ext;
+
+long helib_ext::zzx_to_limbs(uint64_t *limbs, const NTL::ZZX &poly, long len, long limbs_per_coeff)
+{
+    if (NTL::deg(poly) >= len)
+        return report(kInvalidArg, "ZZX has more coefficients than requested");
+
+    for (long i = 0; i < len; i++)
+    {
+        long ret = zz_to_limbs(limbs + i * limbs_per_coeff, NTL::coeff(poly, i), limbs_per_coeff);
+        if (ret != kOk)
+            return ret;
+    }
+    return kOk;
+}
+
+long ZZX_from_limbs(void **zzx, const uint64_t *limbs, long len, long limbs_per_coeff)
+{
+    if (zzx == nullptr || (limbs == nullptr && len > 0))
+        return report_null();
+    if (len < 0 || limbs_per_coeff <= 0)
+        return report(kInvalidArg, "Invalid number of coefficients or limbs");
+
+    try
+    {
+        NTL::ZZX *poly = new NTL::ZZX;
+        poly->SetLength(len);
+        for (long i = 0; i < len; i++)
+            zz_from_limbs(poly->rep[i], limbs + i * limbs_per_coeff, limbs_per_coeff);
+        poly->normalize();
+        *zzx = poly;
+        return kOk;
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
+
+long ZZX_to_limbs(void *zzx, uint64_t *limbs, long len, long limbs_per_coeff)
+{
+    if (zzx == nullptr || (limbs == nullptr && len > 0))
+        return report_null();
+    if (len < 0 || limbs_per_coeff <= 0)
+        return report(kInvalidArg, "Invalid number of coefficients or limbs");
+
+    try
+    {
+        return zzx_to_limbs(limbs, *static_cast<NTL::ZZX *>(zzx), len, limbs_per_coeff);
+    }
+    catch (const std::exception &e)
+    {
+        return report_exception(e);
+    }
+    catch (...)
+    {
+        return report_unknown();
+    }
+}
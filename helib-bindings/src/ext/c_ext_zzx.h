@@ -0,0 +1,22 @@
+#pragma once
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+// Builds a ZZX with len coefficients from limbs, which holds len * limbs_per_coeff
+// 64-bit limbs. Each coefficient is stored as limbs_per_coeff consecutive limbs,
+// least significant first.
+long ZZX_from_limbs(void **zzx, const uint64_t *limbs, long len, long limbs_per_coeff);
+
+// Writes the first len coefficients of zzx into limbs in the layout of
+// ZZX_from_limbs, coefficients beyond the degree are written as zero. Fails with
+// an out of range error if a coefficient is negative or does not fit into
+// limbs_per_coeff limbs.
+long ZZX_to_limbs(void *zzx, uint64_t *limbs, long len, long limbs_per_coeff);
+
+#ifdef __cplusplus
+}
+#endif
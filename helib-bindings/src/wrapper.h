@@ -7,3 +7,14 @@
 #include <helib/c_pubkey.h>
 #include <helib/c_seckey.h>
 #include <helib/c_galoiskey2k.h>
+
+#include "ext/c_ext_error.h"
+#include "ext/c_ext_checked.h"
+#include "ext/c_ext_context.h"
+#include "ext/c_ext_ctxt.h"
+#include "ext/c_ext_keys.h"
+#include "ext/c_ext_galois.h"
+#include "ext/c_ext_seeded.h"
+#include "ext/c_ext_rng.h"
+#include "ext/c_ext_zz.h"
+#include "ext/c_ext_zzx.h"
@@ -1,6 +1,6 @@
 use ark_ff::PrimeField;
 use helib_rs::{
-    matrix::{Bsgs, FFTMatrix},
+    matrix::{Bsgs, BsgsPacking, FFTMatrix},
     BatchEncoder, CLong, Context, Ctxt, EncodedPtxt, Error, GaloisEngine, NTTProcessor, PubKey,
     SecKey, ZZ,
 };
@@ -93,17 +93,15 @@ fn packed_fft<F: PrimeField>(
     root: F,
     context: &mut HeContext<F>,
 ) -> Result<Ctxt, Error> {
-    let n2 = 1 << (dim.ilog2() >> 1);
-    let n1 = dim / n2;
+    let (n1, n2) = Bsgs::default_split(dim);
 
     // Galois keys:
     tracing::info!("Adding missing Galois keys");
     let start = Instant::now();
-    for index in Bsgs::bsgs_indices(n1, n2, context.encoder.slot_count()) {
-        context
-            .galois
-            .generate_key_for_step(&context.seckey, index)?;
-    }
+    let steps = Bsgs::hoisted_galois_steps(dim, context.encoder.slot_count(), BsgsPacking::Packed)?;
+    context
+        .galois
+        .generate_missing_keys(&context.seckey, steps)?;
     let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
     tracing::info!("Adding missing Galois keys took {} ms", duration_ms);
 
@@ -125,19 +123,14 @@ fn fully_packed_fft<F: PrimeField>(
     context: &mut HeContext<F>,
 ) -> Result<Ctxt, Error> {
     let dim = context.encoder.slot_count();
-    let dim_half = dim >> 1;
-    let n2 = 1 << (dim_half.ilog2() >> 1);
-    let n1 = dim_half / n2;
 
     // Galois keys:
     tracing::info!("Adding missing Galois keys");
     let start = Instant::now();
-    for index in Bsgs::bsgs_indices(n1, n2, dim) {
-        context
-            .galois
-            .generate_key_for_step(&context.seckey, index)?;
-    }
-    context.galois.generate_key_for_step(&context.seckey, 0)?;
+    let steps = Bsgs::hoisted_galois_steps(dim, dim, BsgsPacking::FullyPacked)?;
+    context
+        .galois
+        .generate_missing_keys(&context.seckey, steps)?;
     let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
     tracing::info!("Adding missing Galois keys took {} ms", duration_ms);
 
@@ -160,19 +153,14 @@ fn multiple_packed_fft<F: PrimeField>(
     context: &mut HeContext<F>,
 ) -> Result<Vec<Ctxt>, Error> {
     let slots = context.encoder.slot_count();
-    let slots_half = slots >> 1;
-    let n2 = 1 << (slots_half.ilog2() >> 1);
-    let n1 = slots_half / n2;
 
     // Galois keys:
     tracing::info!("Adding missing Galois keys");
     let start = Instant::now();
-    for index in Bsgs::bsgs_indices(n1, n2, slots) {
-        context
-            .galois
-            .generate_key_for_step(&context.seckey, index)?;
-    }
-    context.galois.generate_key_for_step(&context.seckey, 0)?;
+    let steps = Bsgs::hoisted_galois_steps(dim, slots, BsgsPacking::MultipleOfPacksize)?;
+    context
+        .galois
+        .generate_missing_keys(&context.seckey, steps)?;
     let duration_ms = start.elapsed().as_micros() as f64 / 1000.;
     tracing::info!("Adding missing Galois keys took {} ms", duration_ms);
 
@@ -193,14 +181,10 @@ fn fft_selector<F: PrimeField>(
     ctxts: &[Ctxt],
     context: &mut HeContext<F>,
 ) -> Result<Vec<Ctxt>, Error> {
-    let result = match dim.cmp(&context.encoder.slot_count()) {
-        std::cmp::Ordering::Less => {
-            vec![packed_fft(&ctxts[0], dim, root, context)?]
-        }
-        std::cmp::Ordering::Equal => {
-            vec![fully_packed_fft(&ctxts[0], root, context)?]
-        }
-        std::cmp::Ordering::Greater => multiple_packed_fft(ctxts, dim, root, context)?,
+    let result = match BsgsPacking::for_dimension(dim, context.encoder.slot_count()) {
+        BsgsPacking::Packed => vec![packed_fft(&ctxts[0], dim, root, context)?],
+        BsgsPacking::FullyPacked => vec![fully_packed_fft(&ctxts[0], root, context)?],
+        BsgsPacking::MultipleOfPacksize => multiple_packed_fft(ctxts, dim, root, context)?,
     };
     Ok(result)
 }
@@ -1,5 +1,5 @@
 use super::{galois::Galois, ntt::NTTProcessor};
-use crate::CLong;
+use crate::{CLong, Error};
 use ark_ff::PrimeField;
 
 pub struct BatchEncoder<F: PrimeField> {
@@ -9,15 +9,41 @@ pub struct BatchEncoder<F: PrimeField> {
 }
 
 impl<F: PrimeField> BatchEncoder<F> {
+    /// Panics if the field does not support batching for `m`, see [`BatchEncoder::try_new`].
     pub fn new(m: CLong) -> Self {
+        Self::try_new(m).expect("Batching not supported")
+    }
+
+    /// Encoder for the power-of-two cyclotomic `m`. Fails with [`Error::InvalidParameters`] if `m` is not a power of two or the field modulus is not 1 mod `m`, i.e., `m` exceeds [`BatchEncoder::max_m`].
+    ///
+    /// The slots are defined by the minimal primitive root of unity, so all encoders for the same `m` and field are compatible.
+    pub fn try_new(m: CLong) -> Result<Self, Error> {
+        if m < 4 || !(m as u64).is_power_of_two() {
+            return Err(Error::InvalidParameters(format!(
+                "m = {m} is not a power of two >= 4"
+            )));
+        }
+        let max_m = Self::max_m();
+        if m > max_m {
+            return Err(Error::InvalidParameters(format!(
+                "p is not 1 mod {m}, the field supports batching up to m = {max_m}"
+            )));
+        }
+
         let n = m as usize >> 1;
-        let root =
-            Galois::get_minimal_primitive_n_root_of_unity(m as usize).expect("no root found");
-        Self {
+        let root = Galois::get_minimal_primitive_n_root_of_unity(m as usize).ok_or_else(|| {
+            Error::InvalidParameters(format!("No primitive {m}-th root of unity found"))
+        })?;
+        Ok(Self {
             n,
             index_map: Self::populate_index_map(n),
             ntt_proc: NTTProcessor::new_negacylic(n, root),
-        }
+        })
+    }
+
+    /// The largest power-of-two cyclotomic the field supports batching for, i.e., the largest power of two dividing p - 1.
+    pub fn max_m() -> CLong {
+        1 << F::TWO_ADICITY.min(CLong::BITS - 2)
     }
 
     pub fn slot_count(&self) -> usize {
@@ -86,7 +112,7 @@ mod test {
     use super::*;
     use crate::encoding::{negacyclic_naive_mult, rotate_plain};
     use ark_ff::UniformRand;
-    use rand::{thread_rng, Rng};
+    use rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
 
     const NUM_TRIALS: usize = 10;
     const N: usize = 1024;
@@ -102,6 +128,41 @@ mod test {
         assert_eq!(input, result);
     }
 
+    #[test]
+    fn try_new_test() {
+        assert_eq!(BatchEncoder::<ark_bn254::Fr>::max_m(), 1 << 28);
+        assert!(BatchEncoder::<ark_bn254::Fr>::try_new(M as CLong).is_ok());
+        assert!(matches!(
+            BatchEncoder::<ark_bn254::Fr>::try_new(1 << 29),
+            Err(Error::InvalidParameters(_))
+        ));
+        assert!(matches!(
+            BatchEncoder::<ark_bn254::Fr>::try_new(32109),
+            Err(Error::InvalidParameters(_))
+        ));
+
+        // p - 1 of the BN254 base field is only divisible by 2
+        assert_eq!(BatchEncoder::<ark_bn254::Fq>::max_m(), 2);
+        assert!(matches!(
+            BatchEncoder::<ark_bn254::Fq>::try_new(M as CLong),
+            Err(Error::InvalidParameters(_))
+        ));
+    }
+
+    #[test]
+    fn canonical_root() {
+        let root1: ark_bn254::Fr =
+            Galois::get_primitive_n_root_of_unity(M, &mut StdRng::seed_from_u64(42)).unwrap();
+        let root2: ark_bn254::Fr =
+            Galois::get_primitive_n_root_of_unity(M, &mut StdRng::seed_from_u64(42)).unwrap();
+        assert_eq!(root1, root2);
+
+        let input: Vec<_> = (0..N).map(|i| ark_bn254::Fr::from(i as u64)).collect();
+        let encoder1 = BatchEncoder::new(M as CLong);
+        let encoder2 = BatchEncoder::new(M as CLong);
+        assert_eq!(encoder1.encode(&input), encoder2.encode(&input));
+    }
+
     #[test]
     fn batch_add_test() {
         let encoder = BatchEncoder::new(M as CLong);
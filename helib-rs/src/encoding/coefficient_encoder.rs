@@ -0,0 +1,116 @@
+use crate::{CLong, Error};
+use ark_ff::PrimeField;
+use std::marker::PhantomData;
+
+/// Encodes a vector of field elements directly as the coefficients of the plaintext polynomial in Z_p\[X\] / (X^n + 1) with n = m / 2, instead of into SIMD slots.
+///
+/// Adding ciphertexts adds the coefficient vectors, multiplying them multiplies the polynomials negacyclically, i.e., mod X^n + 1 and p. The decrypted product thus equals what [`crate::NTTProcessor`] computes for the two inputs: [`crate::NTTProcessor::negacylcic_preprocess_two`], the forward NTTs, the pointwise product, the inverse NTT and [`crate::NTTProcessor::negacylcic_postprocess`]. In contrast to [`crate::BatchEncoder`], no root of unity is needed, so every field and power-of-two `m` is supported.
+pub struct CoefficientEncoder<F: PrimeField> {
+    n: usize,
+    _field: PhantomData<F>,
+}
+
+impl<F: PrimeField> CoefficientEncoder<F> {
+    /// Panics if `m` is not a power of two, see [`CoefficientEncoder::try_new`].
+    pub fn new(m: CLong) -> Self {
+        Self::try_new(m).expect("Invalid m")
+    }
+
+    /// Encoder for the power-of-two cyclotomic `m`. Fails with [`Error::InvalidParameters`] if `m` is not a power of two >= 4.
+    pub fn try_new(m: CLong) -> Result<Self, Error> {
+        if m < 4 || !(m as u64).is_power_of_two() {
+            return Err(Error::InvalidParameters(format!(
+                "m = {m} is not a power of two >= 4"
+            )));
+        }
+        Ok(Self {
+            n: m as usize >> 1,
+            _field: PhantomData,
+        })
+    }
+
+    /// The degree n of X^n + 1, i.e., the number of coefficients.
+    pub fn coefficient_count(&self) -> usize {
+        self.n
+    }
+
+    /// Pads `input` with zero coefficients to length n.
+    pub fn encode(&self, input: &[F]) -> Vec<F> {
+        assert!(input.len() <= self.n);
+        let mut encoded = input.to_vec();
+        encoded.resize(self.n, F::zero());
+        encoded
+    }
+
+    /// Pads `input` to n coefficients, since NTL drops leading zero coefficients.
+    pub fn decode(&self, input: &[F]) -> Vec<F> {
+        self.encode(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        encoding::{galois::Galois, negacyclic_naive_mult},
+        NTTProcessor,
+    };
+    use ark_ff::UniformRand;
+    use rand::thread_rng;
+
+    const NUM_TRIALS: usize = 5;
+    const N: usize = 1024;
+    const M: usize = 2 * N;
+
+    #[test]
+    fn try_new_test() {
+        assert!(matches!(
+            CoefficientEncoder::<ark_bn254::Fr>::try_new(3 * 1024),
+            Err(Error::InvalidParameters(_))
+        ));
+        assert!(CoefficientEncoder::<ark_bn254::Fr>::try_new(2).is_err());
+        // No roots of unity are required, in contrast to batching
+        let encoder = CoefficientEncoder::<ark_bn254::Fq>::try_new(M as CLong).unwrap();
+        assert_eq!(encoder.coefficient_count(), N);
+    }
+
+    #[test]
+    fn encode_decode_test() {
+        let encoder = CoefficientEncoder::<ark_bn254::Fr>::new(M as CLong);
+        let mut rng = thread_rng();
+        for _ in 0..NUM_TRIALS {
+            let input: Vec<_> = (0..N).map(|_| ark_bn254::Fr::rand(&mut rng)).collect();
+            let encoded = encoder.encode(&input[..N / 2]);
+            assert_eq!(encoded.len(), N);
+            assert_eq!(encoded[..N / 2], input[..N / 2]);
+            assert_eq!(encoder.decode(&encoded[..N / 2]), encoded);
+        }
+    }
+
+    #[test]
+    fn ntt_mult_test() {
+        let encoder = CoefficientEncoder::<ark_bn254::Fr>::new(M as CLong);
+        let root = Galois::get_minimal_primitive_n_root_of_unity(M).expect("no root found!");
+        let ntt_proc = NTTProcessor::new_negacylic(N, root);
+
+        let mut rng = thread_rng();
+        for _ in 0..NUM_TRIALS {
+            let a: Vec<_> = (0..N).map(|_| ark_bn254::Fr::rand(&mut rng)).collect();
+            let b: Vec<_> = (0..N).map(|_| ark_bn254::Fr::rand(&mut rng)).collect();
+            let naive = negacyclic_naive_mult(&encoder.encode(&a), &encoder.encode(&b));
+
+            let (mut a, mut b) = (a, b);
+            ntt_proc.negacylcic_preprocess_two(&mut a, &mut b);
+            let product: Vec<_> = ntt_proc
+                .ntt(&a)
+                .iter()
+                .zip(ntt_proc.ntt(&b).iter())
+                .map(|(a, b)| *a * b)
+                .collect();
+            let mut result = ntt_proc.intt(&product);
+            ntt_proc.negacylcic_postprocess(&mut result);
+
+            assert_eq!(encoder.decode(&result), naive);
+        }
+    }
+}
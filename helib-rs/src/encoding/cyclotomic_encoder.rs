@@ -0,0 +1,1054 @@
+use crate::{CLong, Context, Error};
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+use rand::{rngs::StdRng, SeedableRng};
+
+fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+// m < 2^32, so the products fit into u64
+fn pow_mod(base: usize, mut exp: usize, m: usize) -> usize {
+    let m = m as u64;
+    let mut base = base as u64 % m;
+    let mut res = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            res = res * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+    res as usize
+}
+
+fn prime_factors(mut n: usize) -> Vec<usize> {
+    let mut factors = Vec::new();
+    let mut r = 2;
+    while r * r <= n {
+        if n % r == 0 {
+            factors.push(r);
+            while n % r == 0 {
+                n /= r;
+            }
+        }
+        r += 1;
+    }
+    if n > 1 {
+        factors.push(n);
+    }
+    factors
+}
+
+fn euler_phi(n: usize) -> usize {
+    prime_factors(n).iter().fold(n, |phi, r| phi / r * (r - 1))
+}
+
+fn moebius(n: usize) -> i8 {
+    let factors = prime_factors(n);
+    if factors.iter().any(|r| n % (r * r) == 0) {
+        return 0;
+    }
+    if factors.len() & 1 == 0 {
+        1
+    } else {
+        -1
+    }
+}
+
+// Polynomials over F are coefficient vectors, lowest degree first, without leading zeros
+
+fn poly_trim<F: PrimeField>(mut a: Vec<F>) -> Vec<F> {
+    while a.last().is_some_and(|c| c.is_zero()) {
+        a.pop();
+    }
+    a
+}
+
+// Remainder of a divided by the non-zero b
+fn poly_rem<F: PrimeField>(a: Vec<F>, b: &[F]) -> Vec<F> {
+    let lead_inv = b
+        .last()
+        .and_then(|lead| lead.inverse())
+        .expect("Divisor is not zero");
+    let mut a = poly_trim(a);
+    while a.len() >= b.len() {
+        let factor = *a.last().expect("Not empty") * lead_inv;
+        let shift = a.len() - b.len();
+        for (x, y) in a[shift..].iter_mut().zip(b.iter()) {
+            *x -= factor * y;
+        }
+        a = poly_trim(a);
+    }
+    a
+}
+
+// Quotient of a divided by the non-zero b, which divides a exactly
+fn poly_div<F: PrimeField>(a: &[F], b: &[F]) -> Vec<F> {
+    let lead_inv = b
+        .last()
+        .and_then(|lead| lead.inverse())
+        .expect("Divisor is not zero");
+    let mut rem = a.to_vec();
+    let mut quotient = vec![F::zero(); a.len() + 1 - b.len()];
+    for shift in (0..quotient.len()).rev() {
+        let factor = rem[shift + b.len() - 1] * lead_inv;
+        quotient[shift] = factor;
+        for (x, y) in rem[shift..].iter_mut().zip(b.iter()) {
+            *x -= factor * y;
+        }
+    }
+    quotient
+}
+
+fn poly_mul_rem<F: PrimeField>(a: &[F], b: &[F], modulus: &[F]) -> Vec<F> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut product = vec![F::zero(); a.len() + b.len() - 1];
+    for (i, a) in a.iter().enumerate() {
+        for (j, b) in b.iter().enumerate() {
+            product[i + j] += *a * b;
+        }
+    }
+    poly_rem(product, modulus)
+}
+
+fn poly_pow_rem<F: PrimeField>(base: &[F], exp: &BigUint, modulus: &[F]) -> Vec<F> {
+    let mut result = poly_rem(vec![F::one()], modulus);
+    for i in (0..exp.bits()).rev() {
+        result = poly_mul_rem(&result, &result, modulus);
+        if exp.bit(i) {
+            result = poly_mul_rem(&result, base, modulus);
+        }
+    }
+    result
+}
+
+// Monic greatest common divisor of a and b, which are not both zero
+fn poly_gcd<F: PrimeField>(a: Vec<F>, b: Vec<F>) -> Vec<F> {
+    let (mut a, mut b) = (poly_trim(a), poly_trim(b));
+    while !b.is_empty() {
+        let rem = poly_rem(a, &b);
+        a = b;
+        b = rem;
+    }
+    let lead_inv = a
+        .last()
+        .and_then(|lead| lead.inverse())
+        .expect("Not both zero");
+    a.iter_mut().for_each(|c| *c *= lead_inv);
+    a
+}
+
+// Multiplies by X^e - 1
+fn mul_x_pow_minus_one<F: PrimeField>(poly: &mut Vec<F>, e: usize) {
+    let len = poly.len();
+    poly.resize(len + e, F::zero());
+    // From the top, such that poly[i - e] is still the input coefficient
+    for i in (0..len + e).rev() {
+        let shifted = if i >= e { poly[i - e] } else { F::zero() };
+        poly[i] = shifted - poly[i];
+    }
+}
+
+// Divides by X^e - 1, which divides poly exactly
+fn div_x_pow_minus_one<F: PrimeField>(poly: &mut Vec<F>, e: usize) {
+    // poly = q * X^e - q, solved from the bottom coefficient up
+    let len = poly.len() - e;
+    for i in 0..len {
+        let shifted = if i >= e { poly[i - e] } else { F::zero() };
+        poly[i] = shifted - poly[i];
+    }
+    debug_assert!((len..len + e).all(|i| poly[i] == if i >= e { poly[i - e] } else { F::zero() }));
+    poly.truncate(len);
+}
+
+// Solves sum_j x_j * columns_j = rhs for n columns of length >= n by Gaussian elimination, None
+// if the columns are linearly dependent. rhs has to lie in their span.
+fn solve<F: PrimeField>(columns: &[Vec<F>], rhs: &[F]) -> Option<Vec<F>> {
+    let n = columns.len();
+    let mut rows = (0..rhs.len())
+        .map(|r| {
+            columns
+                .iter()
+                .map(|column| column[r])
+                .chain([rhs[r]])
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    for col in 0..n {
+        let pivot = (col..rows.len()).find(|r| !rows[*r][col].is_zero())?;
+        rows.swap(col, pivot);
+        let inv = rows[col][col].inverse().expect("Pivot is not zero");
+        rows[col].iter_mut().for_each(|x| *x *= inv);
+        let pivot_row = rows[col].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            let factor = row[col];
+            if r != col && !factor.is_zero() {
+                for (x, y) in row.iter_mut().zip(pivot_row.iter()) {
+                    *x -= factor * y;
+                }
+            }
+        }
+    }
+    debug_assert!(rows[n..].iter().all(|row| row[n].is_zero()));
+    rows.truncate(n);
+    Some(rows.into_iter().map(|row| row[n]).collect())
+}
+
+// A subgroup of Z_m^*, built from the Frobenius subgroup <p> by adding generators
+#[derive(Clone)]
+struct Subgroup {
+    m: usize,
+    contains: Vec<bool>,
+    elements: Vec<usize>,
+}
+
+impl Subgroup {
+    fn cyclic(m: usize, g: usize) -> Self {
+        let mut subgroup = Self {
+            m,
+            contains: vec![false; m],
+            elements: Vec::new(),
+        };
+        let mut x = 1;
+        loop {
+            subgroup.contains[x] = true;
+            subgroup.elements.push(x);
+            x = x * g % m;
+            if x == 1 {
+                break;
+            }
+        }
+        subgroup
+    }
+
+    // The order of t in the quotient by this subgroup, which divides the index of the subgroup
+    fn quotient_order(&self, t: usize, index: usize, index_factors: &[usize]) -> usize {
+        let mut ord = index;
+        for r in index_factors.iter() {
+            while ord % r == 0 && self.contains[pow_mod(t, ord / r, self.m)] {
+                ord /= r;
+            }
+        }
+        ord
+    }
+
+    // Adds g, whose order in the quotient is ord
+    fn extend(&mut self, g: usize, ord: usize) {
+        // The cosets g^j * H for 0 < j < ord are distinct and new
+        let mut extension = Vec::with_capacity(self.elements.len() * (ord - 1));
+        let mut g_pow = 1;
+        for _ in 1..ord {
+            g_pow = g_pow * g % self.m;
+            extension.extend(self.elements.iter().map(|h| h * g_pow % self.m));
+        }
+        for x in extension.iter() {
+            self.contains[*x] = true;
+        }
+        self.elements.extend(extension);
+    }
+}
+
+/// The slots of Z_p\[X\] / Phi_m(X) and how the automorphisms X -> X^k permute them, for evaluating rotations.
+///
+/// Phi_m splits into phi(m) / d irreducible factors of degree d = ord_m(p), one per slot. The slots are indexed by the cosets of Z_m^* / \<p\>, which are decomposed into a hypercube: every coset is represented by g_1^e_1 * ... * g_k^e_k with 0 <= e_i < ord_i, where ord_i is the order of g_i modulo \<p, g_1, ..., g_(i-1)\>. A slot is addressed by its coordinates (e_1, ..., e_k), the slot index is their mixed-radix value with e_1 being the most significant.
+///
+/// [`SlotStructure::from_context`] takes the generators of HElib's `PAlgebra`, such that the slot indices follow HElib's hypercube. The generators chosen by [`SlotStructure::new`] need not be HElib's.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SlotStructure {
+    m: usize,
+    p: BigUint,
+    phi_m: usize,
+    ord_p: usize,
+    gens: Vec<usize>,
+    ords: Vec<usize>,
+    native: Vec<bool>,
+    // The representative g_1^e_1 * ... * g_k^e_k of each slot
+    reps: Vec<usize>,
+}
+
+impl SlotStructure {
+    /// The slot structure with generators chosen by this crate: each one has maximal order in the quotient by the previous ones, native ones preferred.
+    pub fn new(m: usize, p: &BigUint) -> Result<Self, Error> {
+        let p_mod = Self::p_mod(m, p)?;
+        let units = (1..m).filter(|t| gcd(*t, m) == 1).collect::<Vec<_>>();
+        let frobenius = Subgroup::cyclic(m, p_mod);
+
+        // Greedily take an element of maximal order in the quotient by the subgroup generated so
+        // far. Such an element generates a direct summand, so the orders multiply up to the
+        // number of slots. Among those, native ones (g^ord in <p>) are preferred.
+        let mut subgroup = frobenius.clone();
+        let mut remaining = units.len() / frobenius.elements.len();
+        let mut gens = Vec::new();
+        while remaining > 1 {
+            let factors = prime_factors(remaining);
+            let mut best: Option<(usize, bool, usize)> = None;
+            for &t in units.iter().filter(|t| !subgroup.contains[**t]) {
+                let ord = subgroup.quotient_order(t, remaining, &factors);
+                let is_native = frobenius.contains[pow_mod(t, ord, m)];
+                if best.map_or(true, |(o, n, _)| (ord, is_native) > (o, n)) {
+                    best = Some((ord, is_native, t));
+                }
+            }
+            let (ord, _, g) = best.expect("Quotient is not trivial");
+            subgroup.extend(g, ord);
+            gens.push(g);
+            remaining /= ord;
+        }
+
+        Self::with_generators(m, p, &gens)
+    }
+
+    /// The slot structure with the given generators, in this order. Fails with [`Error::InvalidParameters`] if a generator is not a unit mod `m`, lies in the subgroup generated by p and the previous generators, or if the generators do not reach all phi(m) / d slots.
+    pub fn with_generators(m: usize, p: &BigUint, gens: &[usize]) -> Result<Self, Error> {
+        let p_mod = Self::p_mod(m, p)?;
+        let phi_m = (1..m).filter(|t| gcd(*t, m) == 1).count();
+        let frobenius = Subgroup::cyclic(m, p_mod);
+        let ord_p = frobenius.elements.len();
+
+        let mut subgroup = frobenius.clone();
+        let mut remaining = phi_m / ord_p;
+        let (mut ords, mut native) = (Vec::new(), Vec::new());
+        for &g in gens.iter() {
+            if g >= m || gcd(g, m) != 1 {
+                return Err(Error::InvalidParameters(format!(
+                    "Generator {g} is not a unit mod {m}"
+                )));
+            }
+            if subgroup.contains[g] {
+                return Err(Error::InvalidParameters(format!(
+                    "Generator {g} is generated by p and the previous generators"
+                )));
+            }
+            let ord = subgroup.quotient_order(g, remaining, &prime_factors(remaining));
+            native.push(frobenius.contains[pow_mod(g, ord, m)]);
+            ords.push(ord);
+            subgroup.extend(g, ord);
+            remaining /= ord;
+        }
+        if remaining != 1 {
+            return Err(Error::InvalidParameters(format!(
+                "The generators {gens:?} reach {} of {} slots",
+                phi_m / ord_p / remaining,
+                phi_m / ord_p
+            )));
+        }
+
+        let mut reps = vec![1];
+        for (g, ord) in gens.iter().zip(ords.iter()) {
+            reps = reps
+                .into_iter()
+                .flat_map(|rep| (0..*ord).map(move |e| rep * pow_mod(*g, e, m) % m))
+                .collect();
+        }
+
+        Ok(Self {
+            m,
+            p: p.to_owned(),
+            phi_m,
+            ord_p,
+            gens: gens.to_vec(),
+            ords,
+            native,
+            reps,
+        })
+    }
+
+    /// The slot structure for the characteristic of `F` as plaintext modulus.
+    pub fn for_field<F: PrimeField>(m: CLong) -> Result<Self, Error> {
+        Self::new(m as usize, &F::MODULUS.into())
+    }
+
+    /// The slot structure with the generators of HElib's `PAlgebra` for `context`, such that slot indices and hypercube dimensions match HElib's.
+    pub fn from_context(context: &Context) -> Result<Self, Error> {
+        let params = context.params();
+        let (gens, ords) = context.slot_generators()?;
+        let structure = Self::with_generators(params.m as usize, &params.p, &gens)?;
+        if structure.ords != ords {
+            return Err(Error::InvalidParameters(format!(
+                "HElib's dimensions {ords:?} differ from {:?}",
+                structure.ords
+            )));
+        }
+        Ok(structure)
+    }
+
+    // p mod m, which has to be a unit
+    fn p_mod(m: usize, p: &BigUint) -> Result<usize, Error> {
+        if m < 2 || m > u32::MAX as usize {
+            return Err(Error::InvalidParameters(format!("m = {m} is out of range")));
+        }
+        let p_mod = u64::try_from(p % m).expect("Reduced mod m") as usize;
+        if gcd(p_mod, m) != 1 {
+            return Err(Error::InvalidParameters(format!(
+                "p and m = {m} are not coprime"
+            )));
+        }
+        Ok(p_mod)
+    }
+
+    pub fn m(&self) -> usize {
+        self.m
+    }
+
+    /// The plaintext modulus.
+    pub fn p(&self) -> &BigUint {
+        &self.p
+    }
+
+    pub fn phi_m(&self) -> usize {
+        self.phi_m
+    }
+    /// The order d of p mod m, i.e., the degree of the slots over F_p.
+    pub fn ord_p(&self) -> usize {
+        self.ord_p
+    }
+
+    pub fn slot_count(&self) -> usize {
+        self.reps.len()
+    }
+
+    /// The generators g_i of the hypercube dimensions.
+    pub fn gens(&self) -> &[usize] {
+        &self.gens
+    }
+
+    /// The sizes ord_i of the hypercube dimensions.
+    pub fn ords(&self) -> &[usize] {
+        &self.ords
+    }
+
+    /// Whether the rotation along `dim` is a single automorphism, i.e., g_dim^ord_dim is in \<p\>.
+    pub fn is_native(&self, dim: usize) -> bool {
+        self.native[dim]
+    }
+
+    pub fn slot_index(&self, coords: &[usize]) -> usize {
+        assert_eq!(coords.len(), self.ords.len());
+        coords
+            .iter()
+            .zip(self.ords.iter())
+            .fold(0, |index, (e, ord)| {
+                assert!(e < ord);
+                index * ord + e
+            })
+    }
+
+    pub fn slot_coords(&self, mut index: usize) -> Vec<usize> {
+        assert!(index < self.slot_count());
+        let mut coords = vec![0; self.ords.len()];
+        for (coord, ord) in coords.iter_mut().zip(self.ords.iter()).rev() {
+            *coord = index % ord;
+            index /= ord;
+        }
+        coords
+    }
+
+    // The element of Z_m^* whose evaluation point carries the slot
+    pub(crate) fn slot_representative(&self, index: usize) -> usize {
+        self.reps[index]
+    }
+
+    /// Rotates the slot values along `dim` to the left, i.e., the slot with coordinate e in `dim` receives the value of the slot with coordinate e + `step` (mod ord_dim).
+    pub fn rotate_slots<T: Clone>(&self, slots: &[T], dim: usize, step: i64) -> Vec<T> {
+        assert_eq!(slots.len(), self.slot_count());
+        let ord = self.ords[dim];
+        let step = step.rem_euclid(ord as i64) as usize;
+        (0..slots.len())
+            .map(|index| {
+                let mut coords = self.slot_coords(index);
+                coords[dim] = (coords[dim] + step) % ord;
+                slots[self.slot_index(&coords)].clone()
+            })
+            .collect()
+    }
+
+    /// The Galois elements k, such that X -> X^k performs [`SlotStructure::rotate_slots`]: the first one on the slots whose source does not wrap around in `dim`, the second one on the others (see [`SlotStructure::wrap_mask`]). For native dimensions, both act the same on the slots and a single automorphism suffices. Otherwise, the two results have to be combined with the mask.
+    pub fn rotation_galois_elements(&self, dim: usize, step: i64) -> (usize, usize) {
+        let (g, ord, m) = (self.gens[dim], self.ords[dim], self.m);
+        let step = step.rem_euclid(ord as i64) as usize;
+        let no_wrap = pow_mod(g, step, m);
+        // g^(step - ord), with the inverse of g^ord being (g^ord)^(phi(m) - 1)
+        let g_ord_inv = pow_mod(pow_mod(g, ord, m), self.phi_m - 1, m);
+        (no_wrap, no_wrap * g_ord_inv % m)
+    }
+
+    /// For each slot, whether its source wraps around when rotating along `dim` by `step`.
+    pub fn wrap_mask(&self, dim: usize, step: i64) -> Vec<bool> {
+        let ord = self.ords[dim];
+        let step = step.rem_euclid(ord as i64) as usize;
+        (0..self.slot_count())
+            .map(|index| self.slot_coords(index)[dim] + step >= ord)
+            .collect()
+    }
+}
+
+/// Packed encoding for any cyclotomic Phi_m with p coprime to m, e.g., m = 32109 of the HElib tests.
+///
+/// Phi_m splits mod p into [`SlotStructure::slot_count`] factors of degree d = ord_m(p), so each slot is an extension field of degree d over F_p. A slot holds one element of `F`, embedded as a constant of its extension field. Slot-wise sums and products and the automorphisms X -> X^k keep the slots constant, so encoded ciphertexts can be added, multiplied and rotated with [`crate::AutomorphismEngine`]. Decoding a slot which is not constant yields its trace divided by d.
+///
+/// Slot i is the factor with the root zeta^r for the representative r of slot i in [`SlotStructure`]. The primitive m-th root of unity zeta is canonical: the smallest one in `F` for d = 1, otherwise a root of the factor whose idempotent in F_p\[X\] / (X^m - 1) has the lexicographically smallest coefficients. Encoders for the same m, p and generators thus agree. With [`CyclotomicEncoder::from_context`] the slots follow HElib's hypercube, but the factor of the first slot is chosen by this crate and need not be the one of HElib's `PAlgebraMod`.
+///
+/// Encoding and decoding use the traces of the powers of zeta, which costs O(m) operations per slot for encoding and O(phi(m)) per slot for decoding. Reducing mod Phi_m costs O(m) per divisor of m. For d > 1, finding zeta decomposes the polynomials mod X^m - 1 fixed by X -> X^p, whose dimension is the number of orbits of multiplication by p on Z_m. More than 256 orbits are rejected with [`Error::InvalidParameters`].
+pub struct CyclotomicEncoder<F: PrimeField> {
+    structure: SlotStructure,
+    // Tr(zeta^k) for 0 <= k < m, the trace from F_p(zeta) down to F_p
+    trace: Vec<F>,
+    m_inv: F,
+    ord_p_inv: F,
+    // (X^m - 1) / Phi_m is the product of X^e - 1 over psi_numerator divided by the one over
+    // psi_denominator
+    psi_numerator: Vec<usize>,
+    psi_denominator: Vec<usize>,
+}
+
+impl<F: PrimeField> CyclotomicEncoder<F> {
+    const MAX_ATTEMPTS: usize = 100;
+    const MAX_ORBITS: usize = 256;
+
+    /// Encoder for the cyclotomic `m` with generators chosen by [`SlotStructure::new`].
+    pub fn try_new(m: CLong) -> Result<Self, Error> {
+        Self::with_structure(SlotStructure::for_field::<F>(m)?)
+    }
+
+    /// Encoder whose slots follow HElib's hypercube for `context`, see [`SlotStructure::from_context`].
+    pub fn from_context(context: &Context) -> Result<Self, Error> {
+        Self::with_structure(SlotStructure::from_context(context)?)
+    }
+
+    /// Encoder for the slots of `structure`. Fails with [`Error::InvalidParameters`] if the plaintext modulus of `structure` is not the characteristic of `F`.
+    pub fn with_structure(structure: SlotStructure) -> Result<Self, Error> {
+        let modulus: BigUint = F::MODULUS.into();
+        if structure.p != modulus {
+            return Err(Error::InvalidParameters(format!(
+                "The slot structure is for p = {}, not for the characteristic of the field",
+                structure.p
+            )));
+        }
+        let m = structure.m;
+        let m_inv = F::from(m as u64).inverse().expect("p does not divide m");
+        let ord_p_inv = F::from(structure.ord_p as u64).inverse().ok_or_else(|| {
+            Error::InvalidParameters(format!("p divides ord_m(p) = {}", structure.ord_p))
+        })?;
+        let trace = if structure.ord_p == 1 {
+            Self::trace_split(m)?
+        } else {
+            Self::trace_extension(&structure)?
+        };
+
+        // Phi_m = prod_{e | m} (X^e - 1)^moebius(m / e), where e = m contributes X^m - 1
+        let divisors = (1..m).filter(|e| m % e == 0).collect::<Vec<_>>();
+        let psi_numerator = divisors
+            .iter()
+            .copied()
+            .filter(|e| moebius(m / e) == -1)
+            .collect();
+        let psi_denominator = divisors
+            .iter()
+            .copied()
+            .filter(|e| moebius(m / e) == 1)
+            .collect();
+
+        Ok(Self {
+            structure,
+            trace,
+            m_inv,
+            ord_p_inv,
+            psi_numerator,
+            psi_denominator,
+        })
+    }
+
+    pub fn structure(&self) -> &SlotStructure {
+        &self.structure
+    }
+
+    pub fn slot_count(&self) -> usize {
+        self.structure.slot_count()
+    }
+
+    /// Encodes up to [`CyclotomicEncoder::slot_count`] values into the phi(m) coefficients of a plaintext polynomial.
+    pub fn encode(&self, input: &[F]) -> Vec<F> {
+        assert!(input.len() <= self.slot_count());
+        let m = self.structure.m;
+        // a_i = 1/m * sum_t c_t * Tr(zeta^(-t * i)) over the slot representatives t
+        let mut poly = vec![F::zero(); m];
+        for (index, val) in input.iter().enumerate() {
+            if val.is_zero() {
+                continue;
+            }
+            let t = self.structure.slot_representative(index);
+            let scaled = *val * self.m_inv;
+            let mut k = 0;
+            for coeff in poly.iter_mut() {
+                *coeff += scaled * self.trace[(m - k) % m];
+                k = (k + t) % m;
+            }
+        }
+        self.reduce(poly)
+    }
+
+    /// Decodes the coefficients of a plaintext polynomial of degree < m into its slot values.
+    pub fn decode(&self, input: &[F]) -> Vec<F> {
+        let m = self.structure.m;
+        assert!(input.len() <= m);
+        // Tr(a(zeta^t)) = sum_i a_i * Tr(zeta^(t * i)), which is d * c_t for a constant slot
+        (0..self.slot_count())
+            .map(|index| {
+                let t = self.structure.slot_representative(index);
+                let mut k = 0;
+                let mut acc = F::zero();
+                for coeff in input.iter() {
+                    acc += *coeff * self.trace[k];
+                    k = (k + t) % m;
+                }
+                acc * self.ord_p_inv
+            })
+            .collect()
+    }
+
+    /// Applies X -> X^k to the encoded polynomial, which permutes the slots, see [`SlotStructure::rotation_galois_elements`].
+    pub fn automorphism(&self, input: &[F], k: usize) -> Vec<F> {
+        let m = self.structure.m;
+        assert_eq!(gcd(k, m), 1);
+        let mut result = vec![F::zero(); m];
+        for (i, coeff) in input.iter().enumerate() {
+            // X^m = 1 mod Phi_m
+            result[(i as u64 * k as u64 % m as u64) as usize] += coeff;
+        }
+        self.reduce(result)
+    }
+
+    // Reduces a polynomial mod Phi_m into phi(m) coefficients. With Psi = (X^m - 1) / Phi_m, the
+    // remainder r satisfies r * Psi = a * Psi mod X^m - 1, and r * Psi has degree < m. Multiplying
+    // and exactly dividing by the factors X^e - 1 of Psi costs O(m) each.
+    fn reduce(&self, poly: Vec<F>) -> Vec<F> {
+        let m = self.structure.m;
+        let mut poly = Self::fold(poly, m);
+        for e in self.psi_numerator.iter() {
+            mul_x_pow_minus_one(&mut poly, *e);
+        }
+        for e in self.psi_denominator.iter() {
+            div_x_pow_minus_one(&mut poly, *e);
+        }
+        let mut poly = Self::fold(poly, m);
+        for e in self.psi_denominator.iter() {
+            mul_x_pow_minus_one(&mut poly, *e);
+        }
+        for e in self.psi_numerator.iter() {
+            div_x_pow_minus_one(&mut poly, *e);
+        }
+        debug_assert_eq!(poly.len(), self.structure.phi_m);
+        poly
+    }
+
+    // Reduces mod X^m - 1 into m coefficients
+    fn fold(poly: Vec<F>, m: usize) -> Vec<F> {
+        let mut folded = vec![F::zero(); m];
+        for (i, coeff) in poly.into_iter().enumerate() {
+            folded[i % m] += coeff;
+        }
+        folded
+    }
+
+    // For d = 1, Tr(zeta^k) = zeta^k for the smallest primitive m-th root of unity zeta in F
+    fn trace_split(m: usize) -> Result<Vec<F>, Error> {
+        let modulus: BigUint = F::MODULUS.into();
+        let cofactor = ((modulus - 1u32) / m).to_u64_digits();
+        let factors = prime_factors(m);
+        let root = (2..Self::MAX_ATTEMPTS as u64 + 2)
+            .map(|x| F::from(x).pow(&cofactor))
+            .find(|root| factors.iter().all(|r| !root.pow([(m / r) as u64]).is_one()))
+            .ok_or_else(|| {
+                Error::InvalidParameters(format!("No primitive {m}-th root of unity found"))
+            })?;
+        let powers = Self::powers(root, m);
+        let zeta = (1..m)
+            .filter(|j| gcd(*j, m) == 1)
+            .map(|j| powers[j])
+            .min()
+            .expect("m >= 2");
+        Ok(Self::powers(zeta, m))
+    }
+
+    fn powers(x: F, m: usize) -> Vec<F> {
+        let mut powers = Vec::with_capacity(m);
+        let mut pow = F::one();
+        for _ in 0..m {
+            powers.push(pow);
+            pow *= x;
+        }
+        powers
+    }
+
+    // For d > 1, zeta lies in an extension of F. The idempotent of F[X] / (X^m - 1) belonging to
+    // the factor of zeta is e = 1/m * sum_k Tr(zeta^(-k)) X^k, so Tr(zeta^k) = m * e_(-k). The
+    // idempotents are fixed by X -> X^p, so their coefficients are constant on the orbits of
+    // multiplication by p on Z_m. These polynomials form a split subalgebra. Its part belonging to
+    // Phi_m has one idempotent per slot, which are found from the roots of the minimal polynomial
+    // of a generator, as in Berlekamp's algorithm.
+    fn trace_extension(structure: &SlotStructure) -> Result<Vec<F>, Error> {
+        let m = structure.m;
+        let slots = structure.slot_count();
+        let p_mod = SlotStructure::p_mod(m, &structure.p)?;
+
+        // The orbit of each element of Z_m and a representative per orbit
+        let mut orbit = vec![usize::MAX; m];
+        let mut reps = Vec::new();
+        for i in 0..m {
+            if orbit[i] == usize::MAX {
+                let mut x = i;
+                while orbit[x] == usize::MAX {
+                    orbit[x] = reps.len();
+                    x = x * p_mod % m;
+                }
+                reps.push(i);
+            }
+        }
+        let n = reps.len();
+        if n > Self::MAX_ORBITS {
+            return Err(Error::InvalidParameters(format!(
+                "Multiplication by p has {n} orbits mod {m}, at most {} are supported",
+                Self::MAX_ORBITS
+            )));
+        }
+
+        // The idempotent of Phi_m has the Ramanujan sums c_m(k) / m as coefficients
+        let m_inv = F::from(m as u64).inverse().expect("p does not divide m");
+        let phi_m = F::from(structure.phi_m as u64);
+        let unit = reps
+            .iter()
+            .map(|k| {
+                let q = m / gcd(*k, m);
+                let sum = F::from(moebius(q) as i64) * phi_m / F::from(euler_phi(q) as u64);
+                sum * m_inv
+            })
+            .collect::<Vec<_>>();
+
+        let modulus: BigUint = F::MODULUS.into();
+        let half = (modulus - 1u32) >> 1;
+        // The random element only steers the search, the idempotents do not depend on it
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..Self::MAX_ATTEMPTS {
+            // Multiplication by alpha in orbit coordinates: (alpha * b)_r = sum_i alpha_i b_(r - i)
+            let alpha = (0..n).map(|_| F::rand(&mut rng)).collect::<Vec<_>>();
+            let mut mul = vec![vec![F::zero(); n]; n];
+            for (row, r) in mul.iter_mut().zip(reps.iter()) {
+                for (i, o) in orbit.iter().enumerate() {
+                    row[orbit[(r + m - i) % m]] += alpha[*o];
+                }
+            }
+
+            // alpha^k times the unit of the Phi_m part, for 0 <= k <= slots
+            let mut powers = Vec::with_capacity(slots + 1);
+            let mut power = unit.clone();
+            for _ in 0..slots {
+                let next = mul
+                    .iter()
+                    .map(|row| row.iter().zip(power.iter()).map(|(a, b)| *a * b).sum())
+                    .collect();
+                powers.push(std::mem::replace(&mut power, next));
+            }
+            powers.push(power);
+
+            // alpha generates the Phi_m part iff its minimal polynomial there has degree slots
+            let Some(coeffs) = solve(&powers[..slots], &powers[slots]) else {
+                continue;
+            };
+            let mut minpoly = coeffs.into_iter().map(|c| -c).collect::<Vec<_>>();
+            minpoly.push(F::one());
+            let mut roots = Vec::with_capacity(slots);
+            if !Self::split_roots(minpoly.clone(), &half, &mut roots) {
+                continue;
+            }
+
+            let mut best: Option<Vec<F>> = None;
+            for root in roots {
+                // minpoly / (X - root) vanishes at all other roots
+                let mut quotient = vec![F::zero(); slots];
+                quotient[slots - 1] = minpoly[slots];
+                for k in (1..slots).rev() {
+                    quotient[k - 1] = minpoly[k] + root * quotient[k];
+                }
+                let value = quotient
+                    .iter()
+                    .rev()
+                    .fold(F::zero(), |acc, c| acc * root + c);
+                let scale = value.inverse().expect("Roots are distinct");
+                let mut idempotent = vec![F::zero(); n];
+                for (q, power) in quotient.iter().zip(powers.iter()) {
+                    for (e, x) in idempotent.iter_mut().zip(power.iter()) {
+                        *e += *q * x;
+                    }
+                }
+                let coeffs = orbit
+                    .iter()
+                    .map(|o| idempotent[*o] * scale)
+                    .collect::<Vec<_>>();
+                if best.as_ref().map_or(true, |b| coeffs < *b) {
+                    best = Some(coeffs);
+                }
+            }
+
+            let e = best.expect("Phi_m has a factor");
+            let m_field = F::from(m as u64);
+            return Ok((0..m).map(|k| e[(m - k) % m] * m_field).collect());
+        }
+        Err(Error::InvalidParameters(
+            "No generator of the fixed subalgebra found".to_string(),
+        ))
+    }
+
+    // Collects the roots of the monic f, a product of distinct linear factors, by splitting it
+    // with gcd(f, (X + delta)^((p - 1) / 2) - 1) for delta = 0, 1, ...
+    fn split_roots(f: Vec<F>, half: &BigUint, roots: &mut Vec<F>) -> bool {
+        match f.len() {
+            0 | 1 => return true,
+            2 => {
+                roots.push(-f[0]);
+                return true;
+            }
+            _ => {}
+        }
+        let mut delta = F::zero();
+        for _ in 0..Self::MAX_ATTEMPTS {
+            let mut h = poly_pow_rem(&[delta, F::one()], half, &f);
+            if h.is_empty() {
+                h.push(F::zero());
+            }
+            h[0] -= F::one();
+            let g = poly_gcd(f.clone(), h);
+            if 1 < g.len() && g.len() < f.len() {
+                let q = poly_div(&f, &g);
+                return Self::split_roots(g, half, roots) && Self::split_roots(q, half, roots);
+            }
+            delta += F::one();
+        }
+        false
+    }
+
+    // Phi_m = prod_{d | m} (X^d - 1)^moebius(m / d)
+    #[cfg(test)]
+    fn cyclotomic_polynomial(m: usize) -> Vec<F> {
+        let divisors = (1..=m).filter(|d| m % d == 0).collect::<Vec<_>>();
+        let mut poly = vec![F::one()];
+        for d in divisors.iter().filter(|d| moebius(m / **d) == 1) {
+            let mut product = vec![F::zero(); poly.len() + d];
+            for (i, coeff) in poly.iter().enumerate() {
+                product[i + d] += coeff;
+                product[i] -= coeff;
+            }
+            poly = product;
+        }
+        for d in divisors.iter().filter(|d| moebius(m / **d) == -1) {
+            // poly = q * X^d - q, solved from the top coefficient down
+            let len = poly.len() - d;
+            let mut quotient = vec![F::zero(); len];
+            for i in (0..len).rev() {
+                let carry = quotient.get(i + d).copied().unwrap_or_default();
+                quotient[i] = poly[i + d] + carry;
+            }
+            poly = quotient;
+        }
+        poly
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_ff::UniformRand;
+    use rand::{thread_rng, Rng};
+
+    type F = ark_bn254::Fr;
+
+    // Plaintext multiplication mod Phi_m
+    fn mul_mod_cyclotomic(encoder: &CyclotomicEncoder<F>, a: &[F], b: &[F]) -> Vec<F> {
+        let mut product = vec![F::from(0u64); a.len() + b.len() - 1];
+        for (i, a) in a.iter().enumerate() {
+            for (j, b) in b.iter().enumerate() {
+                product[i + j] += *a * b;
+            }
+        }
+        encoder.reduce(product)
+    }
+
+    #[test]
+    fn cyclotomic_polynomial_test() {
+        let to_field = |coeffs: &[i64]| coeffs.iter().map(|c| F::from(*c)).collect::<Vec<_>>();
+        assert_eq!(
+            CyclotomicEncoder::<F>::cyclotomic_polynomial(3),
+            to_field(&[1, 1, 1])
+        );
+        assert_eq!(
+            CyclotomicEncoder::<F>::cyclotomic_polynomial(8),
+            to_field(&[1, 0, 0, 0, 1])
+        );
+        assert_eq!(
+            CyclotomicEncoder::<F>::cyclotomic_polynomial(12),
+            to_field(&[1, 0, -1, 0, 1])
+        );
+        assert_eq!(
+            CyclotomicEncoder::<F>::cyclotomic_polynomial(39).len(),
+            24 + 1
+        );
+    }
+
+    #[test]
+    fn slot_structure_test() {
+        // The m used by the HElib tests: the slots are extension fields of degree 690
+        let structure = SlotStructure::for_field::<F>(32109).unwrap();
+        assert_eq!(structure.phi_m(), 16560);
+        assert_eq!(structure.ord_p(), 690);
+        assert_eq!(structure.slot_count(), 24);
+        assert_eq!(structure.ords().iter().product::<usize>(), 24);
+
+        // Power of two: Z_m^* = <5> x <-1>
+        let structure = SlotStructure::for_field::<F>(1 << 12).unwrap();
+        assert_eq!(structure.ord_p(), 1);
+        assert_eq!(structure.ords(), [1 << 10, 2]);
+        assert!((0..2).all(|dim| structure.is_native(dim)));
+
+        let structure = SlotStructure::for_field::<F>(3393).unwrap();
+        assert_eq!(structure.ord_p(), 1);
+        assert_eq!(structure.slot_count(), structure.phi_m());
+        let mut reps = (0..structure.slot_count())
+            .map(|i| structure.slot_representative(i))
+            .collect::<Vec<_>>();
+        reps.sort();
+        reps.dedup();
+        assert_eq!(reps.len(), structure.phi_m());
+        for index in 0..structure.slot_count() {
+            assert_eq!(structure.slot_index(&structure.slot_coords(index)), index);
+        }
+
+        assert!(SlotStructure::new(10, &BigUint::from(5u32)).is_err());
+
+        // Explicit generators, e.g., HElib's, are validated
+        let p = F::MODULUS.into();
+        assert_eq!(
+            SlotStructure::with_generators(3393, &p, structure.gens()).unwrap(),
+            structure
+        );
+        let first = structure.gens()[0];
+        for gens in [
+            vec![],
+            vec![first, first],
+            vec![3],
+            structure.gens()[1..].to_vec(),
+        ] {
+            assert!(matches!(
+                SlotStructure::with_generators(3393, &p, &gens),
+                Err(Error::InvalidParameters(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn reduce_test() {
+        let mut rng = thread_rng();
+        for m in [39, 961, 3393] {
+            let encoder = CyclotomicEncoder::<F>::try_new(m as CLong).unwrap();
+            let cyclotomic = CyclotomicEncoder::<F>::cyclotomic_polynomial(m);
+            let phi_m = encoder.structure().phi_m();
+
+            let poly = (0..2 * m + 5)
+                .map(|_| F::rand(&mut rng))
+                .collect::<Vec<_>>();
+            let mut expected = poly_rem(poly.clone(), &cyclotomic);
+            expected.resize(phi_m, F::from(0u64));
+            assert_eq!(encoder.reduce(poly), expected);
+            assert_eq!(encoder.reduce(cyclotomic), vec![F::from(0u64); phi_m]);
+        }
+    }
+
+    #[test]
+    fn encode_decode_test() {
+        let mut rng = thread_rng();
+        // d = 1 with all phi(m) slots, and extension fields of degree 310 and 690 (the m of the
+        // HElib tests)
+        for (m, slots, ord_p) in [(3393, 2016, 1), (961, 3, 310), (32109, 24, 690)] {
+            let encoder = CyclotomicEncoder::<F>::try_new(m).unwrap();
+            assert_eq!(encoder.slot_count(), slots);
+            assert_eq!(encoder.structure().ord_p(), ord_p);
+            // Tr(1) = d
+            assert_eq!(encoder.trace[0], F::from(ord_p as u64));
+
+            let a = (0..encoder.slot_count())
+                .map(|_| F::rand(&mut rng))
+                .collect::<Vec<_>>();
+            let b = (0..encoder.slot_count())
+                .map(|_| F::rand(&mut rng))
+                .collect::<Vec<_>>();
+            let encoded_a = encoder.encode(&a);
+            assert_eq!(encoded_a.len(), encoder.structure().phi_m());
+            assert_eq!(encoder.decode(&encoded_a), a);
+            // The naive product mod Phi_m costs phi(m)^2 operations
+            if encoder.structure().phi_m() > 4096 {
+                continue;
+            }
+
+            let encoded_b = encoder.encode(&b);
+            let product = a
+                .iter()
+                .zip(b.iter())
+                .map(|(a, b)| *a * b)
+                .collect::<Vec<_>>();
+            let encoded_product = mul_mod_cyclotomic(&encoder, &encoded_a, &encoded_b);
+            assert_eq!(encoder.decode(&encoded_product), product);
+        }
+    }
+
+    #[test]
+    fn canonical_root_test() {
+        // Independently built encoders agree, and the structure has to match the field
+        for m in [39, 961] {
+            let a = CyclotomicEncoder::<F>::try_new(m).unwrap();
+            let b = CyclotomicEncoder::<F>::try_new(m).unwrap();
+            assert_eq!(a.trace, b.trace);
+        }
+        let structure = SlotStructure::for_field::<ark_bn254::Fq>(39).unwrap();
+        assert!(matches!(
+            CyclotomicEncoder::<F>::with_structure(structure),
+            Err(Error::InvalidParameters(_))
+        ));
+    }
+
+    #[test]
+    fn rotation_mapping_test() {
+        let mut rng = thread_rng();
+        for m in [39, 961, 3393] {
+            let encoder = CyclotomicEncoder::<F>::try_new(m).unwrap();
+            let structure = encoder.structure();
+            let input = (0..encoder.slot_count())
+                .map(|_| F::rand(&mut rng))
+                .collect::<Vec<_>>();
+            let encoded = encoder.encode(&input);
+
+            for dim in 0..structure.ords().len() {
+                let step = rng.gen_range(1..structure.ords()[dim] as i64 + 1);
+                let expected = structure.rotate_slots(&input, dim, step);
+                let (no_wrap, wrap) = structure.rotation_galois_elements(dim, step);
+                let rotated_no_wrap = encoder.decode(&encoder.automorphism(&encoded, no_wrap));
+                let rotated_wrap = encoder.decode(&encoder.automorphism(&encoded, wrap));
+                let mask = structure.wrap_mask(dim, step);
+                for index in 0..encoder.slot_count() {
+                    let rotated = if mask[index] {
+                        rotated_wrap[index]
+                    } else {
+                        rotated_no_wrap[index]
+                    };
+                    assert_eq!(rotated, expected[index]);
+                }
+                if structure.is_native(dim) {
+                    assert_eq!(rotated_no_wrap, expected);
+                }
+            }
+        }
+    }
+}
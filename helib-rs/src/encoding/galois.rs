@@ -1,6 +1,6 @@
 use ark_ff::{LegendreSymbol, One, PrimeField};
 use num_bigint::BigUint;
-use rand::thread_rng;
+use rand::{thread_rng, RngCore};
 
 pub(crate) struct Galois {}
 
@@ -37,8 +37,11 @@ impl Galois {
             .collect()
     }
 
-    /// plaintext prime p, for x^n/2 + 1
-    pub(crate) fn get_primitive_n_root_of_unity<F: PrimeField>(n: usize) -> Option<F> {
+    /// plaintext prime p, for x^n/2 + 1, with candidates drawn from `rng`
+    pub(crate) fn get_primitive_n_root_of_unity<F: PrimeField, R: RngCore + ?Sized>(
+        n: usize,
+        rng: &mut R,
+    ) -> Option<F> {
         let p_biguint: BigUint = F::MODULUS.into();
 
         assert_eq!(&p_biguint % n, BigUint::one(), "p must be 1 mod {}", n);
@@ -47,9 +50,8 @@ impl Galois {
         let group_size_u64 = group_size.to_u64_digits();
         let n_pow = [(n >> 1) as u64];
 
-        let mut rand = thread_rng();
         for _ in 0..Self::MAX_ATTEMPTS {
-            let x = F::rand(&mut rand);
+            let x = F::rand(rng);
             let root = x.pow(&group_size_u64);
             let check = root.pow(n_pow) + F::one();
             if check.is_zero() {
@@ -59,12 +61,9 @@ impl Galois {
         None
     }
 
-    /// plaintext prime p, for x^n/2 + 1
+    /// plaintext prime p, for x^n/2 + 1. The minimum does not depend on the random candidates, so it is canonical.
     pub(crate) fn get_minimal_primitive_n_root_of_unity<F: PrimeField>(n: usize) -> Option<F> {
-        let mut root = match Self::get_primitive_n_root_of_unity::<F>(n) {
-            Some(i) => i,
-            None => return None,
-        };
+        let mut root = Self::get_primitive_n_root_of_unity::<F, _>(n, &mut thread_rng())?;
 
         let gen = root.square();
         let mut current_gen = root;
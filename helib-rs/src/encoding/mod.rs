@@ -1,6 +1,9 @@
 pub(crate) mod batch_encoder;
+pub(crate) mod coefficient_encoder;
+pub(crate) mod cyclotomic_encoder;
 pub(crate) mod galois;
 pub(crate) mod ntt;
+pub(crate) mod plaintext_poly;
 
 pub(crate) fn reverse_bits32(input: u32) -> u32 {
     let mut output = ((input & 0xaaaaaaaa) >> 1) | ((input & 0x55555555) << 1);
@@ -22,7 +25,7 @@ pub(crate) fn reverse_n_bits(input: u64, num_bits: u64) -> u64 {
 use ark_ff::PrimeField;
 
 #[cfg(test)]
-fn negacyclic_naive_mult<F: PrimeField>(a: &[F], b: &[F]) -> Vec<F> {
+pub(crate) fn negacyclic_naive_mult<F: PrimeField>(a: &[F], b: &[F]) -> Vec<F> {
     assert!(a.len() == b.len());
     let mut result = vec![F::zero(); a.len()];
     for i in 0..a.len() {
@@ -0,0 +1,327 @@
+use super::galois::Galois;
+use crate::{BatchEncoder, EncodedPtxt, Error};
+use ark_ff::PrimeField;
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial};
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// A plaintext polynomial in Z_p\[X\] / (X^n + 1), with the operations of [`crate::Ctxt`] and [`crate::GaloisEngine`] evaluated in the clear.
+///
+/// Running a circuit on `PlaintextPoly`s and on ciphertexts side by side gives the expected result of every intermediate step, which can be compared slot by slot after decryption. Constants act on the polynomial the same way HElib applies them to ciphertexts, i.e., a field element is the constant polynomial and thus affects every slot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlaintextPoly<F: PrimeField> {
+    coeffs: Vec<F>,
+}
+
+impl<F: PrimeField> PlaintextPoly<F> {
+    /// Takes the n coefficients of the polynomial, n has to be a power of two.
+    pub fn from_coeffs(coeffs: Vec<F>) -> Self {
+        assert!(coeffs.len().is_power_of_two());
+        Self { coeffs }
+    }
+
+    pub fn encode(vec: &[F], batch_encoder: &BatchEncoder<F>) -> Result<Self, Error> {
+        if vec.len() > batch_encoder.slot_count() {
+            return Err(Error::BatchSlots);
+        }
+        Ok(Self::from_coeffs(batch_encoder.encode(vec)))
+    }
+
+    pub fn decode(&self, batch_encoder: &BatchEncoder<F>) -> Result<Vec<F>, Error> {
+        if self.coeffs.len() != batch_encoder.slot_count() {
+            return Err(Error::BatchSlots);
+        }
+        Ok(batch_encoder.decode(&self.coeffs))
+    }
+
+    /// Reads the first n coefficients of `ptxt`, e.g., the result of [`crate::SecKey::packed_decrypt`].
+    pub fn from_encoded_ptxt(ptxt: &EncodedPtxt, n: usize) -> Result<Self, Error> {
+        Ok(Self::from_coeffs(ptxt.coeffs(n)?))
+    }
+
+    pub fn to_encoded_ptxt(&self) -> Result<EncodedPtxt, Error> {
+        EncodedPtxt::from_coeffs(self.coeffs.to_owned())
+    }
+
+    pub fn coeffs(&self) -> &[F] {
+        &self.coeffs
+    }
+
+    pub fn n(&self) -> usize {
+        self.coeffs.len()
+    }
+
+    // Arithmetic
+
+    pub fn poly_add(&self, other: &Self) -> Self {
+        assert_eq!(self.n(), other.n());
+        let coeffs = self
+            .coeffs
+            .iter()
+            .zip(other.coeffs.iter())
+            .map(|(a, b)| *a + b)
+            .collect();
+        Self { coeffs }
+    }
+
+    pub fn poly_sub(&self, other: &Self) -> Self {
+        assert_eq!(self.n(), other.n());
+        let coeffs = self
+            .coeffs
+            .iter()
+            .zip(other.coeffs.iter())
+            .map(|(a, b)| *a - b)
+            .collect();
+        Self { coeffs }
+    }
+
+    pub fn poly_negate(&self) -> Self {
+        let coeffs = self.coeffs.iter().map(|a| -*a).collect();
+        Self { coeffs }
+    }
+
+    /// Negacyclic product, i.e., the product mod X^n + 1 and p.
+    pub fn poly_mul(&self, other: &Self) -> Self {
+        assert_eq!(self.n(), other.n());
+        let n = self.n();
+        let a = DensePolynomial::from_coefficients_slice(&self.coeffs);
+        let b = DensePolynomial::from_coefficients_slice(&other.coeffs);
+        let product = &a * &b;
+
+        // X^n = -1
+        let mut coeffs = vec![F::zero(); n];
+        for (i, coeff) in product.coeffs.iter().enumerate() {
+            if i < n {
+                coeffs[i] += coeff;
+            } else {
+                coeffs[i - n] -= coeff;
+            }
+        }
+        Self { coeffs }
+    }
+
+    // Arithmetic with field elements
+
+    pub fn poly_add_by_field_element(&self, other: F) -> Self {
+        let mut result = self.to_owned();
+        result.coeffs[0] += other;
+        result
+    }
+
+    pub fn poly_sub_by_field_element(&self, other: F) -> Self {
+        let mut result = self.to_owned();
+        result.coeffs[0] -= other;
+        result
+    }
+
+    pub fn poly_mul_by_field_element(&self, other: F) -> Self {
+        let coeffs = self.coeffs.iter().map(|a| *a * other).collect();
+        Self { coeffs }
+    }
+
+    // Rotations
+
+    /// Rotates the slots as [`crate::GaloisEngine::rotate_ctxt`] does, applying the automorphism directly.
+    pub fn rotate(&self, step: i32) -> Self {
+        let galois_elt = Galois::get_elt_from_step(self.n(), step);
+        Self {
+            coeffs: Galois::automorphism(&self.coeffs, galois_elt),
+        }
+    }
+
+    /// Swaps the two rows, see [`crate::GaloisEngine::rotate_ctxt_columns`].
+    pub fn rotate_columns(&self) -> Self {
+        self.rotate(0)
+    }
+}
+
+// Arithmetic with plaintexts
+
+impl<F: PrimeField> Add for &PlaintextPoly<F> {
+    type Output = PlaintextPoly<F>;
+
+    fn add(self, other: &PlaintextPoly<F>) -> PlaintextPoly<F> {
+        self.poly_add(other)
+    }
+}
+
+impl<F: PrimeField> Sub for &PlaintextPoly<F> {
+    type Output = PlaintextPoly<F>;
+
+    fn sub(self, other: &PlaintextPoly<F>) -> PlaintextPoly<F> {
+        self.poly_sub(other)
+    }
+}
+
+impl<F: PrimeField> Neg for &PlaintextPoly<F> {
+    type Output = PlaintextPoly<F>;
+
+    fn neg(self) -> PlaintextPoly<F> {
+        self.poly_negate()
+    }
+}
+
+impl<F: PrimeField> Mul for &PlaintextPoly<F> {
+    type Output = PlaintextPoly<F>;
+
+    fn mul(self, other: &PlaintextPoly<F>) -> PlaintextPoly<F> {
+        self.poly_mul(other)
+    }
+}
+
+// Arithmetic with plaintexts in place
+
+impl<F: PrimeField> AddAssign<&PlaintextPoly<F>> for PlaintextPoly<F> {
+    fn add_assign(&mut self, other: &PlaintextPoly<F>) {
+        *self = self.poly_add(other);
+    }
+}
+
+impl<F: PrimeField> SubAssign<&PlaintextPoly<F>> for PlaintextPoly<F> {
+    fn sub_assign(&mut self, other: &PlaintextPoly<F>) {
+        *self = self.poly_sub(other);
+    }
+}
+
+impl<F: PrimeField> MulAssign<&PlaintextPoly<F>> for PlaintextPoly<F> {
+    fn mul_assign(&mut self, other: &PlaintextPoly<F>) {
+        *self = self.poly_mul(other);
+    }
+}
+
+// Arithmetic with field elements
+
+impl<F: PrimeField> Add<F> for &PlaintextPoly<F> {
+    type Output = PlaintextPoly<F>;
+
+    fn add(self, other: F) -> PlaintextPoly<F> {
+        self.poly_add_by_field_element(other)
+    }
+}
+
+impl<F: PrimeField> Sub<F> for &PlaintextPoly<F> {
+    type Output = PlaintextPoly<F>;
+
+    fn sub(self, other: F) -> PlaintextPoly<F> {
+        self.poly_sub_by_field_element(other)
+    }
+}
+
+impl<F: PrimeField> Mul<F> for &PlaintextPoly<F> {
+    type Output = PlaintextPoly<F>;
+
+    fn mul(self, other: F) -> PlaintextPoly<F> {
+        self.poly_mul_by_field_element(other)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        encoding::{negacyclic_naive_mult, rotate_plain},
+        CLong, Context, GaloisEngine, PubKey, SecKey, ZZ,
+    };
+    use ark_ff::UniformRand;
+    use rand::{thread_rng, Rng};
+
+    const NUM_TRIALS: usize = 5;
+    const N: usize = 1024;
+    const M: usize = 2 * N;
+
+    type F = ark_bn254::Fr;
+
+    fn random_vec(n: usize) -> Vec<F> {
+        let mut rng = thread_rng();
+        (0..n).map(|_| F::rand(&mut rng)).collect()
+    }
+
+    #[test]
+    fn poly_mul_test() {
+        for _ in 0..NUM_TRIALS {
+            let a = random_vec(N);
+            let b = random_vec(N);
+            let product = &PlaintextPoly::from_coeffs(a.to_owned())
+                * &PlaintextPoly::from_coeffs(b.to_owned());
+            assert_eq!(product.coeffs(), negacyclic_naive_mult(&a, &b));
+        }
+    }
+
+    #[test]
+    fn slotwise_arithmetic_test() {
+        let batch_encoder = BatchEncoder::new(M as CLong);
+        let mut rng = thread_rng();
+        for _ in 0..NUM_TRIALS {
+            let a = random_vec(N);
+            let b = random_vec(N);
+            let c = F::rand(&mut rng);
+            let step = rng.gen_range(-(N as i32 / 2) + 1..N as i32 / 2);
+            let poly_a = PlaintextPoly::encode(&a, &batch_encoder).unwrap();
+            let poly_b = PlaintextPoly::encode(&b, &batch_encoder).unwrap();
+
+            // (a * b - a) * c + c, rotated
+            let mut poly = &poly_a * &poly_b;
+            poly -= &poly_a;
+            let poly = &(&poly * c) + c;
+            let rotated = poly.rotate(step);
+            let swapped = poly.rotate_columns();
+
+            let expected = a
+                .iter()
+                .zip(b.iter())
+                .map(|(a, b)| (*a * b - a) * c + c)
+                .collect::<Vec<_>>();
+            assert_eq!(poly.decode(&batch_encoder).unwrap(), expected);
+            assert_eq!(
+                rotated.decode(&batch_encoder).unwrap(),
+                rotate_plain(&expected, step)
+            );
+            assert_eq!(
+                swapped.decode(&batch_encoder).unwrap(),
+                rotate_plain(&expected, 0)
+            );
+            assert_eq!(
+                (-&poly_a).decode(&batch_encoder).unwrap(),
+                a.iter().map(|a| -*a).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn side_by_side_test() {
+        const N: usize = 16384;
+        const M: usize = 2 * N;
+        const STEP: i32 = 5;
+
+        let batch_encoder = BatchEncoder::new(M as CLong);
+
+        let p = ZZ::char::<F>().unwrap();
+        let context = Context::build(M as CLong, &p, 700).unwrap();
+        let seckey = SecKey::build(&context).unwrap();
+        let pubkey = PubKey::from_seckey(&seckey).unwrap();
+        let mut galois = GaloisEngine::build(M as CLong).unwrap();
+        galois.generate_key_for_step(&seckey, STEP).unwrap();
+
+        let a = random_vec(N);
+        let b = random_vec(N);
+        let poly_a = PlaintextPoly::encode(&a, &batch_encoder).unwrap();
+        let poly_b = PlaintextPoly::encode(&b, &batch_encoder).unwrap();
+        let ctxt_a = pubkey
+            .packed_encrypt(&poly_a.to_encoded_ptxt().unwrap())
+            .unwrap();
+        let ctxt_b = pubkey
+            .packed_encrypt(&poly_b.to_encoded_ptxt().unwrap())
+            .unwrap();
+
+        let plain = (&(&poly_a * &poly_b) - &poly_a).rotate(STEP);
+        let mut ctxt = &(&ctxt_a * &ctxt_b) - &ctxt_a;
+        galois.rotate_ctxt(&mut ctxt, STEP).unwrap();
+
+        let decrypted = seckey.packed_decrypt(&ctxt).unwrap();
+        let decrypted = PlaintextPoly::from_encoded_ptxt(&decrypted, N).unwrap();
+        assert_eq!(decrypted, plain);
+        assert_eq!(
+            decrypted.decode(&batch_encoder).unwrap(),
+            plain.decode(&batch_encoder).unwrap()
+        );
+    }
+}
@@ -0,0 +1,269 @@
+use super::{error::Error, rng, CLong};
+use crate::{Context, Ctxt, CyclotomicEncoder, EncodedPtxt, SecKey, SlotStructure};
+use ark_ff::PrimeField;
+use std::{collections::BTreeSet, ffi::c_void, ptr::null_mut};
+
+/// Key-switching matrices for the automorphisms X -> X^k of any cyclotomic, which rotate the slots of a [`CyclotomicEncoder`] along the dimensions of its [`SlotStructure`]. Power-of-two cyclotomics are better served by [`crate::GaloisEngine`], which composes rotations out of few keys.
+#[derive(Debug)]
+pub struct AutomorphismEngine {
+    ptr: *mut c_void,
+    elements: BTreeSet<usize>,
+    // The key-switching matrices refer to the context they were generated under
+    context: Context,
+}
+
+impl AutomorphismEngine {
+    pub fn build(context: &Context) -> Result<Self, Error> {
+        let mut ptr = null_mut();
+        let ret = unsafe { helib_bindings::AK_build(&mut ptr) };
+        Error::error_from_return(ret)?;
+        Ok(Self {
+            ptr,
+            elements: BTreeSet::new(),
+            context: context.to_owned(),
+        })
+    }
+
+    fn check_context(&self, context: &Context) -> Result<(), Error> {
+        if self.context.fingerprint() != context.fingerprint() {
+            return Err(Error::ContextMismatch);
+        }
+        Ok(())
+    }
+
+    // The rotation has to act on the slots of this context
+    fn check_dimension(&self, structure: &SlotStructure, dim: usize) -> Result<(), Error> {
+        let params = self.context.params();
+        if structure.m() != params.m as usize || structure.p() != &params.p {
+            return Err(Error::InvalidParameters(
+                "The slot structure does not belong to the context".to_string(),
+            ));
+        }
+        if dim >= structure.ords().len() {
+            return Err(Error::InvalidParameters(format!(
+                "Dimension {dim} does not exist, the hypercube has {} dimensions",
+                structure.ords().len()
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn destroy(&mut self) -> Result<(), Error> {
+        if self.ptr.is_null() {
+            return Ok(());
+        }
+
+        let ret = unsafe { helib_bindings::AK_destroy(self.ptr) };
+        Error::error_from_return(ret)?;
+        self.ptr = null_mut();
+        Ok(())
+    }
+
+    /// Generates the key-switching matrix for X -> X^k. Fails with [`Error::InvalidParameters`] if `k` is not a unit mod m.
+    pub fn generate_key(&mut self, seckey: &SecKey, k: usize) -> Result<(), Error> {
+        self.check_context(seckey.context())?;
+        rng::ensure_thread_seeded()?;
+        let ret = unsafe { helib_bindings::AK_generate(self.ptr, seckey.ptr(), k as CLong) };
+        Error::error_from_return(ret)?;
+        self.elements.insert(k);
+        Ok(())
+    }
+
+    /// Generates the keys [`AutomorphismEngine::rotate_ctxt`] needs for rotating along `dim` by `step` the engine does not hold yet, i.e., one key for native dimensions and two otherwise. Returns the number of generated keys.
+    pub fn generate_rotation_keys(
+        &mut self,
+        seckey: &SecKey,
+        structure: &SlotStructure,
+        dim: usize,
+        step: i64,
+    ) -> Result<usize, Error> {
+        self.check_dimension(structure, dim)?;
+        let mut generated = 0;
+        for k in Self::rotation_elements(structure, dim, step) {
+            if !self.has_key(k) {
+                self.generate_key(seckey, k)?;
+                generated += 1;
+            }
+        }
+        Ok(generated)
+    }
+
+    // The automorphisms a rotation along dim applies, none for a multiple of the dimension size
+    fn rotation_elements(structure: &SlotStructure, dim: usize, step: i64) -> Vec<usize> {
+        if step.rem_euclid(structure.ords()[dim] as i64) == 0 {
+            return Vec::new();
+        }
+        let (no_wrap, wrap) = structure.rotation_galois_elements(dim, step);
+        if structure.is_native(dim) {
+            vec![no_wrap]
+        } else {
+            vec![no_wrap, wrap]
+        }
+    }
+
+    pub fn has_key(&self, k: usize) -> bool {
+        self.elements.contains(&k)
+    }
+
+    /// The Galois elements k the engine holds keys for, in ascending order.
+    pub fn available_elements(&self) -> impl Iterator<Item = usize> + '_ {
+        self.elements.iter().copied()
+    }
+
+    /// Applies X -> X^k to `ctxt`. Fails with [`Error::MissingAutomorphismKey`] without a key for `k`.
+    pub fn automorph_ctxt(&self, ctxt: &mut Ctxt, k: usize) -> Result<(), Error> {
+        self.check_context(ctxt.context())?;
+        if !self.has_key(k) {
+            return Err(Error::MissingAutomorphismKey { element: k });
+        }
+        let ret = unsafe { helib_bindings::AK_automorph(self.ptr, ctxt.ptr, k as CLong) };
+        Error::error_from_return(ret)
+    }
+
+    /// Rotates the slots of `ctxt`, which are encoded with `encoder`, along `dim` by `step` like [`SlotStructure::rotate_slots`]. A native dimension takes one automorphism. Otherwise both Galois elements of [`SlotStructure::rotation_galois_elements`] are applied and combined with [`SlotStructure::wrap_mask`], which costs a second automorphism and a multiplication by the encoded mask.
+    pub fn rotate_ctxt<F: PrimeField>(
+        &self,
+        ctxt: &mut Ctxt,
+        encoder: &CyclotomicEncoder<F>,
+        dim: usize,
+        step: i64,
+    ) -> Result<(), Error> {
+        self.check_context(ctxt.context())?;
+        let structure = encoder.structure();
+        self.check_dimension(structure, dim)?;
+        let elements = Self::rotation_elements(structure, dim, step);
+        if let Some(k) = elements.iter().find(|k| !self.has_key(**k)) {
+            return Err(Error::MissingAutomorphismKey { element: *k });
+        }
+
+        match elements.as_slice() {
+            [] => Ok(()),
+            [no_wrap] => self.automorph_ctxt(ctxt, *no_wrap),
+            [no_wrap, wrap] => {
+                let mut wrapped = ctxt.ctxt_clone()?;
+                self.automorph_ctxt(&mut wrapped, *wrap)?;
+                self.automorph_ctxt(ctxt, *no_wrap)?;
+
+                // ctxt + mask * (wrapped - ctxt) takes the wrapped slots from wrapped
+                let mask = structure
+                    .wrap_mask(dim, step)
+                    .into_iter()
+                    .map(|wraps| if wraps { F::one() } else { F::zero() })
+                    .collect::<Vec<_>>();
+                let mask = EncodedPtxt::encode_cyclotomic(&mask, encoder)?;
+                wrapped.ctxt_sub_inplace(ctxt)?;
+                wrapped.ctxt_mul_by_packed_constant_inplace(&mask)?;
+                ctxt.ctxt_add_inplace(&wrapped)
+            }
+            _ => unreachable!("At most two automorphisms per rotation"),
+        }
+    }
+}
+
+impl Drop for AutomorphismEngine {
+    fn drop(&mut self) {
+        self.destroy().expect("AutomorphismEngine destroy failed");
+    }
+}
+
+// SAFETY: Keys are only added through `&mut self`. Automorphisms only read the key-switching
+// matrices, such that one engine can serve concurrent rotations of different ciphertexts.
+unsafe impl Send for AutomorphismEngine {}
+unsafe impl Sync for AutomorphismEngine {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{PubKey, ZZ};
+    use ark_ff::UniformRand;
+    use rand::{thread_rng, Rng};
+
+    // p has order 690 mod m, so the 24 slots are extension fields
+    const M: CLong = 32109;
+
+    #[test]
+    fn rotate_ctxt_test() {
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context = Context::build(M, &p, 700).unwrap();
+        let seckey = SecKey::build(&context).unwrap();
+        let pubkey = PubKey::from_seckey(&seckey).unwrap();
+        let encoder = CyclotomicEncoder::from_context(&context).unwrap();
+        let structure = encoder.structure();
+
+        let mut rng = thread_rng();
+        let input = (0..encoder.slot_count())
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let ctxt = pubkey
+            .packed_encrypt(&EncodedPtxt::encode_cyclotomic(&input, &encoder).unwrap())
+            .unwrap();
+
+        let mut engine = AutomorphismEngine::build(&context).unwrap();
+        for dim in 0..structure.ords().len() {
+            let step = rng.gen_range(1..structure.ords()[dim] as i64);
+            engine
+                .generate_rotation_keys(&seckey, structure, dim, step)
+                .unwrap();
+            let mut rotated = ctxt.ctxt_clone().unwrap();
+            engine
+                .rotate_ctxt(&mut rotated, &encoder, dim, step)
+                .unwrap();
+            let decrypted = seckey
+                .packed_decrypt(&rotated)
+                .unwrap()
+                .decode_cyclotomic(&encoder)
+                .unwrap();
+            assert_eq!(decrypted, structure.rotate_slots(&input, dim, step));
+
+            // A full turn needs no key
+            let mut unchanged = ctxt.ctxt_clone().unwrap();
+            let ord = structure.ords()[dim] as i64;
+            engine
+                .rotate_ctxt(&mut unchanged, &encoder, dim, ord)
+                .unwrap();
+            let decrypted = seckey
+                .packed_decrypt(&unchanged)
+                .unwrap()
+                .decode_cyclotomic(&encoder)
+                .unwrap();
+            assert_eq!(decrypted, input);
+        }
+    }
+
+    #[test]
+    fn missing_key() {
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context = Context::build(M, &p, 700).unwrap();
+        let seckey = SecKey::build(&context).unwrap();
+        let pubkey = PubKey::from_seckey(&seckey).unwrap();
+        let encoder = CyclotomicEncoder::<ark_bn254::Fr>::from_context(&context).unwrap();
+        let mut ctxt = pubkey.encrypt(&ZZ::from_long(1).unwrap()).unwrap();
+
+        let mut engine = AutomorphismEngine::build(&context).unwrap();
+        let (no_wrap, _) = encoder.structure().rotation_galois_elements(0, 1);
+        assert_eq!(
+            engine.rotate_ctxt(&mut ctxt, &encoder, 0, 1),
+            Err(Error::MissingAutomorphismKey { element: no_wrap })
+        );
+        assert!(matches!(
+            engine.rotate_ctxt(&mut ctxt, &encoder, encoder.structure().ords().len(), 1),
+            Err(Error::InvalidParameters(_))
+        ));
+        // 3 divides m
+        assert!(matches!(
+            engine.generate_key(&seckey, 3),
+            Err(Error::InvalidParameters(_))
+        ));
+
+        engine.generate_key(&seckey, no_wrap).unwrap();
+        assert!(engine.has_key(no_wrap));
+        assert_eq!(engine.available_elements().collect::<Vec<_>>(), [no_wrap]);
+
+        let other = Context::build(M, &p, 750).unwrap();
+        let other_seckey = SecKey::build(&other).unwrap();
+        assert_eq!(
+            engine.generate_key(&other_seckey, no_wrap),
+            Err(Error::ContextMismatch)
+        );
+    }
+}
@@ -1,53 +1,211 @@
-use super::{error::Error, CLong};
-use crate::ZZ;
-use std::{ffi::c_void, ptr::null_mut};
+use super::{error::Error, serialization::Fingerprint, CLong};
+use crate::{BatchEncoder, ZZ};
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+use std::{
+    ffi::c_void,
+    ptr::null_mut,
+    sync::{Arc, Mutex},
+};
+
+/// The parameters a [`Context`] is built from. All parties exchanging keys or ciphertexts have to agree on them, which can be checked by comparing [`ContextParams::fingerprint`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ContextParams {
+    /// Index of the cyclotomic polynomial
+    pub m: CLong,
+    /// Plaintext modulus
+    pub p: BigUint,
+    /// Bit size of the ciphertext modulus chain
+    pub bits: CLong,
+}
+
+impl ContextParams {
+    pub fn new(m: CLong, p: BigUint, bits: CLong) -> Self {
+        Self { m, p, bits }
+    }
+
+    /// Parameters with the characteristic of `F` as plaintext modulus.
+    pub fn for_field<F: PrimeField>(m: CLong, bits: CLong) -> Self {
+        Self::new(m, F::MODULUS.into(), bits)
+    }
+
+    /// SHA-256 hash over a canonical encoding of the parameters. Stable across versions and platforms.
+    pub fn fingerprint(&self) -> Fingerprint {
+        let p_bytes = self.p.to_bytes_le();
+        let mut hasher = Sha256::new();
+        hasher.update(b"helib-rs context");
+        hasher.update(self.m.to_le_bytes());
+        hasher.update((p_bytes.len() as u64).to_le_bytes());
+        hasher.update(&p_bytes);
+        hasher.update(self.bits.to_le_bytes());
+        hasher.finalize().into()
+    }
+}
 
 #[derive(Debug)]
-pub struct Context {
+pub(crate) struct ContextInner {
     pub(crate) ptr: *mut c_void,
+    pub(crate) params: ContextParams,
+    pub(crate) fingerprint: Fingerprint,
+    // Key-less HElib public key which ciphertexts loaded by Ctxt::from_bytes are bound to. Created
+    // on first use and shared, since HElib only combines ciphertexts bound to the same key.
+    empty_pubkey: Mutex<*mut c_void>,
+}
+
+impl ContextInner {
+    fn destroy(&mut self) -> Result<(), Error> {
+        if self.ptr.is_null() {
+            return Ok(());
+        }
+
+        // The public key refers to the context
+        let empty_pubkey = self.empty_pubkey.get_mut().expect("Lock poisoned");
+        if !empty_pubkey.is_null() {
+            let ret = unsafe { helib_bindings::pubkey_destroy(*empty_pubkey) };
+            Error::error_from_return(ret)?;
+            *empty_pubkey = null_mut();
+        }
+
+        let ret = unsafe { helib_bindings::context_destroy(self.ptr) };
+        Error::error_from_return(ret)?;
+        self.ptr = null_mut();
+        Ok(())
+    }
+}
+
+impl Drop for ContextInner {
+    fn drop(&mut self) {
+        self.destroy().expect("Context destroy failed");
+    }
+}
+
+// SAFETY: The HElib context is immutable once built and only read by the objects derived from it.
+// HElib itself reads it from multiple threads in its internal parallel loops.
+unsafe impl Send for ContextInner {}
+unsafe impl Sync for ContextInner {}
+
+/// Handle to an HElib context. Keys, ciphertexts and Galois keys derived from it keep the underlying context alive, so the handle can be dropped (or cloned) freely.
+#[derive(Clone, Debug)]
+pub struct Context {
+    pub(crate) inner: Arc<ContextInner>,
 }
 
 impl Context {
     pub fn build(m: CLong, p: &ZZ, bits: CLong) -> Result<Self, Error> {
+        let params = ContextParams::new(m, p.to_biguint()?, bits);
+        Self::build_inner(params, p)
+    }
+
+    pub fn from_params(params: &ContextParams) -> Result<Self, Error> {
+        let p = ZZ::from_biguint(params.p.to_owned())?;
+        Self::build_inner(params.to_owned(), &p)
+    }
+
+    fn build_inner(params: ContextParams, p: &ZZ) -> Result<Self, Error> {
+        if params.m < 2 {
+            return Err(Error::InvalidParameters(format!("m = {} < 2", params.m)));
+        }
+        if params.bits <= 0 {
+            return Err(Error::InvalidParameters(format!(
+                "bits = {} <= 0",
+                params.bits
+            )));
+        }
+        if params.p < BigUint::from(2u32) {
+            return Err(Error::InvalidParameters(format!("p = {} < 2", params.p)));
+        }
         let mut ptr = null_mut();
-        let ret = unsafe { helib_bindings::context_build(&mut ptr, m, p.ptr, bits) };
+        let ret =
+            unsafe { helib_bindings::ext_context_build(&mut ptr, params.m, p.ptr, params.bits) };
         Error::error_from_return(ret)?;
-        Ok(Self { ptr })
+        let fingerprint = params.fingerprint();
+        Ok(Self {
+            inner: Arc::new(ContextInner {
+                ptr,
+                params,
+                fingerprint,
+                empty_pubkey: Mutex::new(null_mut()),
+            }),
+        })
+    }
+
+    pub(crate) fn ptr(&self) -> *mut c_void {
+        self.inner.ptr
+    }
+
+    pub(crate) fn empty_pubkey_ptr(&self) -> Result<*mut c_void, Error> {
+        let mut empty_pubkey = self.inner.empty_pubkey.lock().expect("Lock poisoned");
+        if empty_pubkey.is_null() {
+            let ret = unsafe { helib_bindings::pubkey_empty(&mut *empty_pubkey, self.ptr()) };
+            Error::error_from_return(ret)?;
+        }
+        Ok(*empty_pubkey)
+    }
+
+    pub fn params(&self) -> &ContextParams {
+        &self.inner.params
+    }
+
+    /// See [`ContextParams::fingerprint`]. Objects serialized under one context can only be deserialized under a context with the same fingerprint.
+    pub fn fingerprint(&self) -> Fingerprint {
+        self.inner.fingerprint
+    }
+
+    // A power-of-two m provides m / 2 slots, which the encoder has to fill completely
+    pub(crate) fn check_batch_encoder<F: PrimeField>(
+        &self,
+        batch_encoder: &BatchEncoder<F>,
+    ) -> Result<(), Error> {
+        if (self.params().m >> 1) as usize != batch_encoder.slot_count() {
+            return Err(Error::BatchSlots);
+        }
+        Ok(())
     }
 
     pub fn security_level(&self) -> Result<f64, Error> {
         let mut res = 0f64;
-        let ret = unsafe { helib_bindings::context_get_security_level(self.ptr, &mut res) };
+        let ret = unsafe { helib_bindings::ext_context_get_security_level(self.ptr(), &mut res) };
         Error::error_from_return(ret)?;
         Ok(res)
     }
 
+    // Generators and dimension sizes of the hypercube of HElib's PAlgebra, in HElib's order
+    pub(crate) fn slot_generators(&self) -> Result<(Vec<usize>, Vec<usize>), Error> {
+        let mut len = 0;
+        let ret = unsafe { helib_bindings::context_num_gens(self.ptr(), &mut len) };
+        Error::error_from_return(ret)?;
+        let mut gens = vec![0; len as usize];
+        let mut ords = vec![0; len as usize];
+        let ret = unsafe {
+            helib_bindings::context_gens(self.ptr(), gens.as_mut_ptr(), ords.as_mut_ptr(), len)
+        };
+        Error::error_from_return(ret)?;
+        Ok((
+            gens.into_iter().map(|g| g as usize).collect(),
+            ords.into_iter().map(|ord| ord as usize).collect(),
+        ))
+    }
+
+    /// Destroys the HElib context. Fails if keys, ciphertexts or other handles still refer to it, in which case it is destroyed once the last of them is dropped.
     pub fn destroy(&mut self) -> Result<(), Error> {
-        if self.ptr.is_null() {
-            return Ok(());
+        match Arc::get_mut(&mut self.inner) {
+            Some(inner) => inner.destroy(),
+            None => Err(Error::Other("Context is still in use".to_string())),
         }
-
-        let ret = unsafe { helib_bindings::context_destroy(self.ptr) };
-        Error::error_from_return(ret)?;
-        self.ptr = null_mut();
-        Ok(())
     }
 
     pub fn printout(&self) -> Result<(), Error> {
-        let ret = unsafe { helib_bindings::context_printout(self.ptr) };
+        let ret = unsafe { helib_bindings::ext_context_printout(self.ptr()) };
         Error::error_from_return(ret)
     }
 }
 
-impl Drop for Context {
-    fn drop(&mut self) {
-        self.destroy().expect("Context destroy failed");
-    }
-}
-
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::SecKey;
 
     #[test]
     fn build_context() {
@@ -56,6 +214,38 @@ mod test {
         context.destroy().unwrap(); // Is also called in drop
     }
 
+    #[test]
+    fn destroy_context_in_use() {
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let mut context = Context::build(32109, &p, 700).unwrap();
+        let seckey = SecKey::build(&context).unwrap();
+        assert!(context.destroy().is_err());
+        drop(seckey);
+        context.destroy().unwrap();
+    }
+
+    #[test]
+    fn build_context_invalid_parameters() {
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        assert!(matches!(
+            Context::build(1, &p, 700),
+            Err(Error::InvalidParameters(_))
+        ));
+        assert!(matches!(
+            Context::build(32109, &p, 0),
+            Err(Error::InvalidParameters(_))
+        ));
+        assert!(matches!(
+            Context::build(32109, &ZZ::from_long(1).unwrap(), 700),
+            Err(Error::InvalidParameters(_))
+        ));
+        // Rejected by the C layer with a message instead of an unknown error
+        match Context::build(14, &ZZ::from_long(7).unwrap(), 700) {
+            Err(Error::InvalidParameters(message)) => assert!(message.contains("coprime")),
+            res => panic!("Unexpected result {res:?}"),
+        }
+    }
+
     #[test]
     fn context_get_security_level() {
         let p = ZZ::char::<ark_bn254::Fr>().unwrap();
@@ -63,6 +253,52 @@ mod test {
         let _level = context.security_level().unwrap();
     }
 
+    #[test]
+    fn context_fingerprint() {
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context1 = Context::build(32109, &p, 700).unwrap();
+        let context2 = Context::build(32109, &p, 700).unwrap();
+        let context3 = Context::build(32109, &p, 750).unwrap();
+        assert_eq!(context1.fingerprint(), context2.fingerprint());
+        assert_ne!(context1.fingerprint(), context3.fingerprint());
+    }
+
+    #[test]
+    fn context_from_params() {
+        let params = ContextParams::for_field::<ark_bn254::Fr>(32109, 700);
+        let context = Context::from_params(&params).unwrap();
+        assert_eq!(context.params(), &params);
+
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context_ = Context::build(32109, &p, 700).unwrap();
+        assert_eq!(context_.params(), &params);
+        assert_eq!(context.fingerprint(), context_.fingerprint());
+    }
+
+    #[test]
+    fn params_fingerprint_is_stable() {
+        let params = ContextParams::for_field::<ark_bn254::Fr>(32109, 700);
+        let expected = "608da3d441412223b81fc348283dea714968feca3c4f7f4ee4535b4f0e8c8e50";
+        let fingerprint: String = params
+            .fingerprint()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        assert_eq!(fingerprint, expected);
+
+        let other = ContextParams::for_field::<ark_bn254::Fr>(32109, 750);
+        assert_ne!(params.fingerprint(), other.fingerprint());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn params_serde() {
+        let params = ContextParams::for_field::<ark_bn254::Fr>(32109, 700);
+        let bytes = bincode::serialize(&params).unwrap();
+        let params_: ContextParams = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(params, params_);
+    }
+
     #[test]
     #[ignore]
     fn print_context() {
@@ -1,20 +1,60 @@
-use super::{error::Error, CLong};
-use crate::{EncodedPtxt, ZZ};
+use super::{
+    error::Error,
+    pubkey::PubKeyInner,
+    seckey::SecKeyInner,
+    serialization::{self, Fingerprint},
+    CLong,
+};
+use crate::{Context, EncodedPtxt, PubKey, ZZ};
 use ark_ff::PrimeField;
 use std::{
     ffi::c_void,
     ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
     ptr::null_mut,
+    sync::Arc,
 };
 
+/// The key an HElib ciphertext refers to. Holding it keeps the key and its context alive.
+#[derive(Clone, Debug)]
+pub(crate) enum CtxtOwner {
+    SecKey(Arc<SecKeyInner>),
+    PubKey(Arc<PubKeyInner>),
+    // Bound to the key-less public key of the context, see Ctxt::from_bytes
+    Context(Context),
+}
+
+impl CtxtOwner {
+    pub(crate) fn context(&self) -> &Context {
+        match self {
+            CtxtOwner::SecKey(key) => &key.context,
+            CtxtOwner::PubKey(key) => &key.context,
+            CtxtOwner::Context(context) => context,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Ctxt {
     pub(crate) ptr: *mut c_void,
+    pub(crate) owner: CtxtOwner,
 }
 
 impl Ctxt {
-    pub(crate) fn empty_pointer() -> Self {
-        Self { ptr: null_mut() }
+    const MAGIC: &'static [u8; 4] = b"HECT";
+
+    pub(crate) fn empty_pointer(owner: CtxtOwner) -> Self {
+        Self {
+            ptr: null_mut(),
+            owner,
+        }
+    }
+
+    pub fn context(&self) -> &Context {
+        self.owner.context()
+    }
+
+    pub(crate) fn fingerprint(&self) -> Fingerprint {
+        self.context().fingerprint()
     }
 
     pub fn destroy(&mut self) -> Result<(), Error> {
@@ -30,14 +70,59 @@ impl Ctxt {
 
     pub fn noise_budget(&self) -> Result<CLong, Error> {
         let mut res = 0;
-        let ret = unsafe { helib_bindings::ctxt_get_noise_budget(self.ptr, &mut res) };
+        let ret = unsafe { helib_bindings::ext_ctxt_get_noise_budget(self.ptr, &mut res) };
         Error::error_from_return(ret)?;
         Ok(res)
     }
 
     pub fn ctxt_clone(&self) -> Result<Ctxt, Error> {
-        let mut ctxt = Ctxt::empty_pointer();
-        let ret = unsafe { helib_bindings::ctxt_clone(&mut ctxt.ptr, self.ptr) };
+        let mut ctxt = Ctxt::empty_pointer(self.owner.clone());
+        let ret = unsafe { helib_bindings::ext_ctxt_clone(&mut ctxt.ptr, self.ptr) };
+        Error::error_from_return(ret)?;
+        Ok(ctxt)
+    }
+
+    // Serialization
+
+    /// Serializes the ciphertext into a versioned binary format which records the fingerprint of its context.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut len = 0;
+        let ret = unsafe { helib_bindings::ctxt_bytes(self.ptr, &mut len) };
+        Error::error_from_return(ret)?;
+
+        let mut buf = serialization::write_header(Self::MAGIC, &self.fingerprint(), len as usize);
+        let offset = buf.len();
+        buf.resize(offset + len as usize, 0);
+        let ret =
+            unsafe { helib_bindings::ctxt_to_bytes(self.ptr, buf[offset..].as_mut_ptr(), len) };
+        Error::error_from_return(ret)?;
+        Ok(buf)
+    }
+
+    /// Deserializes a ciphertext produced by [`Ctxt::to_bytes`] under `context`. Fails with [`Error::ContextMismatch`] if the ciphertext was serialized under a different context.
+    ///
+    /// HElib binds every ciphertext to a public key, so the ciphertext is bound to a key-less public key shared by all ciphertexts loaded under `context`. These can be added, subtracted, multiplied by constants, decrypted and serialized again. Operations which need the key material of a real public key, such as multiplying two ciphertexts (relinearization), need [`Ctxt::from_bytes_with_pubkey`]. HElib does not combine ciphertexts bound to different public keys.
+    pub fn from_bytes(context: &Context, buf: &[u8]) -> Result<Self, Error> {
+        let pubkey = context.empty_pubkey_ptr()?;
+        Self::from_bytes_inner(CtxtOwner::Context(context.to_owned()), pubkey, buf)
+    }
+
+    /// Deserializes a ciphertext produced by [`Ctxt::to_bytes`] under the context of `pubkey` and binds it to `pubkey`, such that it can be combined with ciphertexts encrypted under `pubkey`. Fails with [`Error::ContextMismatch`] if the ciphertext was serialized under a different context.
+    pub fn from_bytes_with_pubkey(pubkey: &PubKey, buf: &[u8]) -> Result<Self, Error> {
+        Self::from_bytes_inner(CtxtOwner::PubKey(pubkey.inner.clone()), pubkey.ptr(), buf)
+    }
+
+    fn from_bytes_inner(owner: CtxtOwner, pubkey: *mut c_void, buf: &[u8]) -> Result<Self, Error> {
+        let mut ctxt = Ctxt::empty_pointer(owner);
+        let payload = serialization::read_header(Self::MAGIC, &ctxt.fingerprint(), buf)?;
+        let ret = unsafe {
+            helib_bindings::ctxt_from_bytes(
+                &mut ctxt.ptr,
+                pubkey,
+                payload.as_ptr(),
+                payload.len() as CLong,
+            )
+        };
         Error::error_from_return(ret)?;
         Ok(ctxt)
     }
@@ -45,29 +130,29 @@ impl Ctxt {
     // Arithmetic
 
     pub fn ctxt_add(&self, other: &Ctxt) -> Result<Ctxt, Error> {
-        let mut ctxt = Ctxt::empty_pointer();
-        let ret = unsafe { helib_bindings::ctxt_add(&mut ctxt.ptr, self.ptr, other.ptr) };
+        let mut ctxt = Ctxt::empty_pointer(self.owner.clone());
+        let ret = unsafe { helib_bindings::ext_ctxt_add(&mut ctxt.ptr, self.ptr, other.ptr) };
         Error::error_from_return(ret)?;
         Ok(ctxt)
     }
 
     pub fn ctxt_sub(&self, other: &Ctxt) -> Result<Ctxt, Error> {
-        let mut ctxt = Ctxt::empty_pointer();
-        let ret = unsafe { helib_bindings::ctxt_sub(&mut ctxt.ptr, self.ptr, other.ptr) };
+        let mut ctxt = Ctxt::empty_pointer(self.owner.clone());
+        let ret = unsafe { helib_bindings::ext_ctxt_sub(&mut ctxt.ptr, self.ptr, other.ptr) };
         Error::error_from_return(ret)?;
         Ok(ctxt)
     }
 
     pub fn ctxt_negate(&self) -> Result<Ctxt, Error> {
-        let mut ctxt = Ctxt::empty_pointer();
-        let ret = unsafe { helib_bindings::ctxt_negate(&mut ctxt.ptr, self.ptr) };
+        let mut ctxt = Ctxt::empty_pointer(self.owner.clone());
+        let ret = unsafe { helib_bindings::ext_ctxt_negate(&mut ctxt.ptr, self.ptr) };
         Error::error_from_return(ret)?;
         Ok(ctxt)
     }
 
     pub fn ctxt_mul(&self, other: &Ctxt) -> Result<Ctxt, Error> {
-        let mut ctxt = Ctxt::empty_pointer();
-        let ret = unsafe { helib_bindings::ctxt_mult(&mut ctxt.ptr, self.ptr, other.ptr) };
+        let mut ctxt = Ctxt::empty_pointer(self.owner.clone());
+        let ret = unsafe { helib_bindings::ext_ctxt_mult(&mut ctxt.ptr, self.ptr, other.ptr) };
         Error::error_from_return(ret)?;
         Ok(ctxt)
     }
@@ -75,17 +160,17 @@ impl Ctxt {
     // Arithmetic in place
 
     pub fn ctxt_add_inplace(&mut self, other: &Ctxt) -> Result<(), Error> {
-        let ret = unsafe { helib_bindings::ctxt_add_inplace(self.ptr, other.ptr) };
+        let ret = unsafe { helib_bindings::ext_ctxt_add_inplace(self.ptr, other.ptr) };
         Error::error_from_return(ret)
     }
 
     pub fn ctxt_sub_inplace(&mut self, other: &Ctxt) -> Result<(), Error> {
-        let ret = unsafe { helib_bindings::ctxt_sub_inplace(self.ptr, other.ptr) };
+        let ret = unsafe { helib_bindings::ext_ctxt_sub_inplace(self.ptr, other.ptr) };
         Error::error_from_return(ret)
     }
 
     pub fn ctxt_negate_inplace(&mut self) -> Result<(), Error> {
-        let ret = unsafe { helib_bindings::ctxt_negate_inplace(self.ptr) };
+        let ret = unsafe { helib_bindings::ext_ctxt_negate_inplace(self.ptr) };
         Error::error_from_return(ret)
     }
 
@@ -95,40 +180,42 @@ impl Ctxt {
     }
 
     pub fn ctxt_mul_inplace(&mut self, other: &Ctxt) -> Result<(), Error> {
-        let ret = unsafe { helib_bindings::ctxt_mult_inplace(self.ptr, other.ptr) };
+        let ret = unsafe { helib_bindings::ext_ctxt_mult_inplace(self.ptr, other.ptr) };
         Error::error_from_return(ret)
     }
 
     // Arithmetic with constants
 
     pub fn ctxt_add_by_constant(&self, other: &ZZ) -> Result<Ctxt, Error> {
-        let mut ctxt = Ctxt::empty_pointer();
+        let mut ctxt = Ctxt::empty_pointer(self.owner.clone());
         let ret =
-            unsafe { helib_bindings::ctxt_add_by_constant(&mut ctxt.ptr, self.ptr, other.ptr) };
+            unsafe { helib_bindings::ext_ctxt_add_by_constant(&mut ctxt.ptr, self.ptr, other.ptr) };
         Error::error_from_return(ret)?;
         Ok(ctxt)
     }
 
     pub fn ctxt_sub_by_constant(&self, other: &ZZ) -> Result<Ctxt, Error> {
-        let mut ctxt = Ctxt::empty_pointer();
+        let mut ctxt = Ctxt::empty_pointer(self.owner.clone());
         let ret =
-            unsafe { helib_bindings::ctxt_sub_by_constant(&mut ctxt.ptr, self.ptr, other.ptr) };
+            unsafe { helib_bindings::ext_ctxt_sub_by_constant(&mut ctxt.ptr, self.ptr, other.ptr) };
         Error::error_from_return(ret)?;
         Ok(ctxt)
     }
 
     pub fn ctxt_sub_from_constant(&self, other: &ZZ) -> Result<Ctxt, Error> {
-        let mut ctxt = Ctxt::empty_pointer();
-        let ret =
-            unsafe { helib_bindings::ctxt_sub_from_constant(&mut ctxt.ptr, other.ptr, self.ptr) };
+        let mut ctxt = Ctxt::empty_pointer(self.owner.clone());
+        let ret = unsafe {
+            helib_bindings::ext_ctxt_sub_from_constant(&mut ctxt.ptr, other.ptr, self.ptr)
+        };
         Error::error_from_return(ret)?;
         Ok(ctxt)
     }
 
     pub fn ctxt_mul_by_constant(&self, other: &ZZ) -> Result<Ctxt, Error> {
-        let mut ctxt = Ctxt::empty_pointer();
-        let ret =
-            unsafe { helib_bindings::ctxt_mult_by_constant(&mut ctxt.ptr, self.ptr, other.ptr) };
+        let mut ctxt = Ctxt::empty_pointer(self.owner.clone());
+        let ret = unsafe {
+            helib_bindings::ext_ctxt_mult_by_constant(&mut ctxt.ptr, self.ptr, other.ptr)
+        };
         Error::error_from_return(ret)?;
         Ok(ctxt)
     }
@@ -136,36 +223,36 @@ impl Ctxt {
     // Arithmetic with packed constants
 
     pub fn ctxt_add_by_packed_constant(&self, other: &EncodedPtxt) -> Result<Ctxt, Error> {
-        let mut ctxt = Ctxt::empty_pointer();
+        let mut ctxt = Ctxt::empty_pointer(self.owner.clone());
         let ret = unsafe {
-            helib_bindings::ctxt_add_by_packed_constant(&mut ctxt.ptr, self.ptr, other.ptr)
+            helib_bindings::ext_ctxt_add_by_packed_constant(&mut ctxt.ptr, self.ptr, other.ptr)
         };
         Error::error_from_return(ret)?;
         Ok(ctxt)
     }
 
     pub fn ctxt_sub_by_packed_constant(&self, other: &EncodedPtxt) -> Result<Ctxt, Error> {
-        let mut ctxt = Ctxt::empty_pointer();
+        let mut ctxt = Ctxt::empty_pointer(self.owner.clone());
         let ret = unsafe {
-            helib_bindings::ctxt_sub_by_packed_constant(&mut ctxt.ptr, self.ptr, other.ptr)
+            helib_bindings::ext_ctxt_sub_by_packed_constant(&mut ctxt.ptr, self.ptr, other.ptr)
         };
         Error::error_from_return(ret)?;
         Ok(ctxt)
     }
 
     pub fn ctxt_sub_from_packed_constant(&self, other: &EncodedPtxt) -> Result<Ctxt, Error> {
-        let mut ctxt = Ctxt::empty_pointer();
+        let mut ctxt = Ctxt::empty_pointer(self.owner.clone());
         let ret = unsafe {
-            helib_bindings::ctxt_sub_from_packed_constant(&mut ctxt.ptr, other.ptr, self.ptr)
+            helib_bindings::ext_ctxt_sub_from_packed_constant(&mut ctxt.ptr, other.ptr, self.ptr)
         };
         Error::error_from_return(ret)?;
         Ok(ctxt)
     }
 
     pub fn ctxt_mul_by_packed_constant(&self, other: &EncodedPtxt) -> Result<Ctxt, Error> {
-        let mut ctxt = Ctxt::empty_pointer();
+        let mut ctxt = Ctxt::empty_pointer(self.owner.clone());
         let ret = unsafe {
-            helib_bindings::ctxt_mult_by_packed_constant(&mut ctxt.ptr, self.ptr, other.ptr)
+            helib_bindings::ext_ctxt_mult_by_packed_constant(&mut ctxt.ptr, self.ptr, other.ptr)
         };
         Error::error_from_return(ret)?;
         Ok(ctxt)
@@ -174,22 +261,23 @@ impl Ctxt {
     // Arithmetic with constants in place
 
     pub fn ctxt_add_by_constant_inplace(&mut self, other: &ZZ) -> Result<(), Error> {
-        let ret = unsafe { helib_bindings::ctxt_add_by_constant_inplace(self.ptr, other.ptr) };
+        let ret = unsafe { helib_bindings::ext_ctxt_add_by_constant_inplace(self.ptr, other.ptr) };
         Error::error_from_return(ret)
     }
 
     pub fn ctxt_sub_by_constant_inplace(&mut self, other: &ZZ) -> Result<(), Error> {
-        let ret = unsafe { helib_bindings::ctxt_sub_by_constant_inplace(self.ptr, other.ptr) };
+        let ret = unsafe { helib_bindings::ext_ctxt_sub_by_constant_inplace(self.ptr, other.ptr) };
         Error::error_from_return(ret)
     }
 
     pub fn ctxt_sub_from_constant_inplace(&mut self, other: &ZZ) -> Result<(), Error> {
-        let ret = unsafe { helib_bindings::ctxt_sub_from_constant_inplace(self.ptr, other.ptr) };
+        let ret =
+            unsafe { helib_bindings::ext_ctxt_sub_from_constant_inplace(self.ptr, other.ptr) };
         Error::error_from_return(ret)
     }
 
     pub fn ctxt_mul_by_constant_inplace(&mut self, other: &ZZ) -> Result<(), Error> {
-        let ret = unsafe { helib_bindings::ctxt_mult_by_constant_inplace(self.ptr, other.ptr) };
+        let ret = unsafe { helib_bindings::ext_ctxt_mult_by_constant_inplace(self.ptr, other.ptr) };
         Error::error_from_return(ret)
     }
 
@@ -200,7 +288,7 @@ impl Ctxt {
         other: &EncodedPtxt,
     ) -> Result<(), Error> {
         let ret =
-            unsafe { helib_bindings::ctxt_add_by_packed_constant_inplace(self.ptr, other.ptr) };
+            unsafe { helib_bindings::ext_ctxt_add_by_packed_constant_inplace(self.ptr, other.ptr) };
         Error::error_from_return(ret)
     }
 
@@ -209,7 +297,7 @@ impl Ctxt {
         other: &EncodedPtxt,
     ) -> Result<(), Error> {
         let ret =
-            unsafe { helib_bindings::ctxt_sub_by_packed_constant_inplace(self.ptr, other.ptr) };
+            unsafe { helib_bindings::ext_ctxt_sub_by_packed_constant_inplace(self.ptr, other.ptr) };
         Error::error_from_return(ret)
     }
 
@@ -217,8 +305,9 @@ impl Ctxt {
         &mut self,
         other: &EncodedPtxt,
     ) -> Result<(), Error> {
-        let ret =
-            unsafe { helib_bindings::ctxt_sub_from_packed_constant_inplace(self.ptr, other.ptr) };
+        let ret = unsafe {
+            helib_bindings::ext_ctxt_sub_from_packed_constant_inplace(self.ptr, other.ptr)
+        };
         Error::error_from_return(ret)
     }
 
@@ -226,8 +315,9 @@ impl Ctxt {
         &mut self,
         other: &EncodedPtxt,
     ) -> Result<(), Error> {
-        let ret =
-            unsafe { helib_bindings::ctxt_mult_by_packed_constant_inplace(self.ptr, other.ptr) };
+        let ret = unsafe {
+            helib_bindings::ext_ctxt_mult_by_packed_constant_inplace(self.ptr, other.ptr)
+        };
         Error::error_from_return(ret)
     }
 
@@ -294,6 +384,12 @@ impl Drop for Ctxt {
     }
 }
 
+// SAFETY: The ciphertext is owned exclusively by this wrapper. All functions taking `&self` only
+// read it (arithmetic creates new ciphertexts), mutation requires `&mut self`. Randomness drawn
+// inside HElib comes from NTL's thread-local random stream.
+unsafe impl Send for Ctxt {}
+unsafe impl Sync for Ctxt {}
+
 // Arithmetic
 
 impl Add for &Ctxt {
@@ -534,7 +630,7 @@ impl Clone for Ctxt {
 mod test {
     use crate::{
         helib::{pubkey::PubKey, seckey::SecKey},
-        Context, ZZ,
+        Context, Ctxt, Error, ZZ,
     };
     use ark_ff::UniformRand;
     use rand::thread_rng;
@@ -586,6 +682,118 @@ mod test {
         }
     }
 
+    #[test]
+    fn ctxt_serialize() {
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context = Context::build(32109, &p, 700).unwrap();
+        let seckey = SecKey::build(&context).unwrap();
+        let pubkey = PubKey::from_seckey(&seckey).unwrap();
+        let mut rng = thread_rng();
+        for _ in 0..TESTRUNS {
+            let input = ark_bn254::Fr::rand(&mut rng);
+            let ctxt = pubkey.encrypt_fieldelement(input).unwrap();
+            let bytes = ctxt.to_bytes().unwrap();
+            let ctxt_ = Ctxt::from_bytes(&context, &bytes).unwrap();
+            let output = seckey
+                .decrypt_fieldelement::<ark_bn254::Fr>(&ctxt_)
+                .unwrap();
+            assert_eq!(output, input);
+
+            // Ciphertexts loaded under the same context can be combined
+            let sum = &ctxt_ + &Ctxt::from_bytes(&context, &bytes).unwrap();
+            let output = seckey.decrypt_fieldelement::<ark_bn254::Fr>(&sum).unwrap();
+            assert_eq!(output, input + input);
+        }
+    }
+
+    #[test]
+    fn ctxt_serialize_with_pubkey() {
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context = Context::build(32109, &p, 700).unwrap();
+        let seckey = SecKey::build(&context).unwrap();
+        let pubkey = PubKey::from_seckey(&seckey).unwrap();
+        let mut rng = thread_rng();
+        let input = ark_bn254::Fr::rand(&mut rng);
+        let ctxt = pubkey.encrypt_fieldelement(input).unwrap();
+        let bytes = ctxt.to_bytes().unwrap();
+        let ctxt_ = Ctxt::from_bytes_with_pubkey(&pubkey, &bytes).unwrap();
+        drop(context);
+
+        let product = &ctxt_ * &ctxt;
+        let output = seckey
+            .decrypt_fieldelement::<ark_bn254::Fr>(&product)
+            .unwrap();
+        assert_eq!(output, input * input);
+    }
+
+    #[test]
+    fn ctxt_deserialize_wrong_context() {
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context1 = Context::build(32109, &p, 700).unwrap();
+        let context2 = Context::build(32109, &p, 750).unwrap();
+        let seckey1 = SecKey::build(&context1).unwrap();
+        let seckey2 = SecKey::build(&context2).unwrap();
+        let pubkey1 = PubKey::from_seckey(&seckey1).unwrap();
+        let pubkey2 = PubKey::from_seckey(&seckey2).unwrap();
+        let mut rng = thread_rng();
+        let ctxt = pubkey1
+            .encrypt_fieldelement(ark_bn254::Fr::rand(&mut rng))
+            .unwrap();
+        let bytes = ctxt.to_bytes().unwrap();
+        assert_eq!(
+            Ctxt::from_bytes(&context2, &bytes).unwrap_err(),
+            Error::ContextMismatch
+        );
+        assert_eq!(
+            Ctxt::from_bytes_with_pubkey(&pubkey2, &bytes).unwrap_err(),
+            Error::ContextMismatch
+        );
+    }
+
+    #[test]
+    fn ctxt_ops_wrong_context() {
+        // Equal parameters, but distinct HElib contexts
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context1 = Context::build(32109, &p, 700).unwrap();
+        let context2 = Context::build(32109, &p, 700).unwrap();
+        let seckey1 = SecKey::build(&context1).unwrap();
+        let seckey2 = SecKey::build(&context2).unwrap();
+        let mut rng = thread_rng();
+        let mut ctxt1 = seckey1
+            .encrypt_fieldelement(ark_bn254::Fr::rand(&mut rng))
+            .unwrap();
+        let ctxt2 = seckey2
+            .encrypt_fieldelement(ark_bn254::Fr::rand(&mut rng))
+            .unwrap();
+
+        assert_eq!(ctxt1.ctxt_add(&ctxt2).unwrap_err(), Error::ContextMismatch);
+        assert_eq!(ctxt1.ctxt_sub(&ctxt2).unwrap_err(), Error::ContextMismatch);
+        assert_eq!(ctxt1.ctxt_mul(&ctxt2).unwrap_err(), Error::ContextMismatch);
+        assert_eq!(
+            ctxt1.ctxt_mul_inplace(&ctxt2).unwrap_err(),
+            Error::ContextMismatch
+        );
+    }
+
+    #[test]
+    fn ctxt_outlives_pubkey_and_context() {
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context = Context::build(32109, &p, 700).unwrap();
+        let seckey = SecKey::build(&context).unwrap();
+        let mut pubkey = PubKey::from_seckey(&seckey).unwrap();
+        drop(context);
+
+        let mut rng = thread_rng();
+        let input = ark_bn254::Fr::rand(&mut rng);
+        let mut ctxt = pubkey.encrypt_fieldelement(input).unwrap();
+        assert!(pubkey.destroy().is_err());
+        drop(pubkey);
+
+        ctxt += &ctxt.clone();
+        let output = seckey.decrypt_fieldelement::<ark_bn254::Fr>(&ctxt).unwrap();
+        assert_eq!(output, input + input);
+    }
+
     #[test]
     fn ctxt_arithmetic_test() {
         let p = ZZ::char::<ark_bn254::Fr>().unwrap();
@@ -1,6 +1,6 @@
 use super::{error::Error, CLong};
-use crate::{BatchEncoder, ZZ};
-use ark_ff::PrimeField;
+use crate::{BatchEncoder, CoefficientEncoder, CyclotomicEncoder, ZZ};
+use ark_ff::{BigInteger, PrimeField};
 use std::{ffi::c_void, ptr::null_mut};
 
 #[derive(Debug)]
@@ -13,32 +13,89 @@ impl EncodedPtxt {
         Self { ptr: null_mut() }
     }
 
+    // Per-coefficient access, the encoders go through the bulk limb conversion instead
+    #[cfg(any(test, feature = "serde"))]
     pub(crate) fn from_len(len: usize) -> Result<Self, Error> {
         let mut ptr = null_mut();
-        let ret = unsafe { helib_bindings::ZZX_from_len(&mut ptr, len as CLong) };
+        let ret = unsafe { helib_bindings::ext_ZZX_from_len(&mut ptr, len as CLong) };
         Error::error_from_return(ret)?;
         Ok(Self { ptr })
     }
 
+    #[cfg(any(test, feature = "serde"))]
     pub(crate) fn set_index(&mut self, index: usize, value: &ZZ) -> Result<(), Error> {
-        let ret = unsafe { helib_bindings::ZZX_set_index(self.ptr, index as CLong, value.ptr) };
+        let ret = unsafe { helib_bindings::ext_ZZX_set_index(self.ptr, index as CLong, value.ptr) };
         Error::error_from_return(ret)
     }
 
+    #[cfg(any(test, feature = "serde"))]
     pub(crate) fn get_index(&self, index: usize) -> Result<ZZ, Error> {
         let mut zz = ZZ::empty_pointer();
-        let ret = unsafe { helib_bindings::ZZX_get_index(&mut zz.ptr, self.ptr, index as CLong) };
+        let ret =
+            unsafe { helib_bindings::ext_ZZX_get_index(&mut zz.ptr, self.ptr, index as CLong) };
         Error::error_from_return(ret)?;
         Ok(zz)
     }
 
     pub(crate) fn get_len(&self) -> Result<usize, Error> {
         let mut len = 0;
-        let ret = unsafe { helib_bindings::ZZX_get_length(self.ptr, &mut len) };
+        let ret = unsafe { helib_bindings::ext_ZZX_get_length(self.ptr, &mut len) };
         Error::error_from_return(ret)?;
         Ok(len as usize)
     }
 
+    // Crosses the FFI boundary once, with each coefficient as the little-endian limbs of its
+    // canonical representative
+    pub(crate) fn from_coeffs<F: PrimeField>(coeffs: Vec<F>) -> Result<Self, Error> {
+        let limbs_per_coeff = F::BigInt::NUM_LIMBS;
+        let mut limbs = Vec::with_capacity(coeffs.len() * limbs_per_coeff);
+        for coeff in coeffs.iter() {
+            limbs.extend_from_slice(coeff.into_bigint().as_ref());
+        }
+        let mut ptr = null_mut();
+        let ret = unsafe {
+            helib_bindings::ZZX_from_limbs(
+                &mut ptr,
+                limbs.as_ptr(),
+                coeffs.len() as CLong,
+                limbs_per_coeff as CLong,
+            )
+        };
+        Error::error_from_return(ret)?;
+        Ok(Self { ptr })
+    }
+
+    // Reads the first len coefficients, padded with zeros beyond the degree
+    pub(crate) fn coeffs<F: PrimeField>(&self, len: usize) -> Result<Vec<F>, Error> {
+        if self.get_len()? > len {
+            return Err(Error::BatchSlots);
+        }
+        let limbs_per_coeff = F::BigInt::NUM_LIMBS;
+        let mut limbs = vec![0u64; len * limbs_per_coeff];
+        let ret = unsafe {
+            helib_bindings::ZZX_to_limbs(
+                self.ptr,
+                limbs.as_mut_ptr(),
+                len as CLong,
+                limbs_per_coeff as CLong,
+            )
+        };
+        Error::error_from_return(ret)?;
+        Self::coeffs_from_limbs(&limbs)
+    }
+
+    // Inverse of the limb layout of from_coeffs
+    pub(crate) fn coeffs_from_limbs<F: PrimeField>(limbs: &[u64]) -> Result<Vec<F>, Error> {
+        limbs
+            .chunks_exact(F::BigInt::NUM_LIMBS)
+            .map(|chunk| {
+                let mut bigint = F::BigInt::default();
+                bigint.as_mut().copy_from_slice(chunk);
+                ZZ::fieldelement_from_bigint(bigint)
+            })
+            .collect()
+    }
+
     pub fn encode<F: PrimeField>(
         vec: &[F],
         batch_encoder: &BatchEncoder<F>,
@@ -46,28 +103,50 @@ impl EncodedPtxt {
         if vec.len() > batch_encoder.slot_count() {
             return Err(Error::BatchSlots);
         }
-        let encoded = batch_encoder.encode(vec);
-
-        let mut encoded_ptxt = EncodedPtxt::from_len(encoded.len())?;
-        for (i, val) in encoded.into_iter().enumerate() {
-            let zz = ZZ::from_fieldelement(val)?;
-            encoded_ptxt.set_index(i, &zz)?;
-        }
-        Ok(encoded_ptxt)
+        Self::from_coeffs(batch_encoder.encode(vec))
     }
 
     pub fn decode<F: PrimeField>(&self, batch_encoder: &BatchEncoder<F>) -> Result<Vec<F>, Error> {
-        let len = self.get_len()?;
-        let mut read = Vec::with_capacity(len);
-        for i in 0..len {
-            let zz = self.get_index(i)?;
-            let val = zz.to_fieldelement()?;
-            read.push(val);
+        let read = self.coeffs(batch_encoder.slot_count())?;
+        Ok(batch_encoder.decode(&read))
+    }
+
+    /// Encodes `vec` as the coefficients of the plaintext polynomial, see [`CoefficientEncoder`].
+    pub fn encode_coefficients<F: PrimeField>(
+        vec: &[F],
+        encoder: &CoefficientEncoder<F>,
+    ) -> Result<Self, Error> {
+        if vec.len() > encoder.coefficient_count() {
+            return Err(Error::BatchSlots);
         }
-        if read.len() != batch_encoder.slot_count() {
+        Self::from_coeffs(encoder.encode(vec))
+    }
+
+    pub fn decode_coefficients<F: PrimeField>(
+        &self,
+        encoder: &CoefficientEncoder<F>,
+    ) -> Result<Vec<F>, Error> {
+        let read = self.coeffs(encoder.coefficient_count())?;
+        Ok(encoder.decode(&read))
+    }
+
+    /// Like [`EncodedPtxt::encode`] for the cyclotomic `m` of `encoder`, with one element of `F` per slot as described in [`crate::CyclotomicEncoder`]. The slots follow HElib's hypercube if the encoder was built with [`crate::CyclotomicEncoder::from_context`].
+    pub fn encode_cyclotomic<F: PrimeField>(
+        vec: &[F],
+        encoder: &CyclotomicEncoder<F>,
+    ) -> Result<Self, Error> {
+        if vec.len() > encoder.slot_count() {
             return Err(Error::BatchSlots);
         }
-        Ok(batch_encoder.decode(&read))
+        Self::from_coeffs(encoder.encode(vec))
+    }
+
+    pub fn decode_cyclotomic<F: PrimeField>(
+        &self,
+        encoder: &CyclotomicEncoder<F>,
+    ) -> Result<Vec<F>, Error> {
+        let read = self.coeffs(encoder.structure().phi_m())?;
+        Ok(encoder.decode(&read))
     }
 
     pub fn destroy(&mut self) -> Result<(), Error> {
@@ -88,10 +167,15 @@ impl Drop for EncodedPtxt {
     }
 }
 
+// SAFETY: NTL::ZZX is a plain coefficient vector owned by this wrapper. Modification requires
+// `&mut self`.
+unsafe impl Send for EncodedPtxt {}
+unsafe impl Sync for EncodedPtxt {}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{Context, PubKey, SecKey};
+    use crate::{encoding::negacyclic_naive_mult, Context, PubKey, SecKey};
     use ark_ff::UniformRand;
     use rand::thread_rng;
 
@@ -119,6 +203,27 @@ mod test {
         }
     }
 
+    #[test]
+    fn encoded_ptxt_limbs_test() {
+        let mut rng = thread_rng();
+        for _ in 0..TESTRUNS {
+            let input: Vec<_> = (0..N).map(|_| ark_bn254::Fr::rand(&mut rng)).collect();
+            let mut ptxt = EncodedPtxt::from_coeffs(input.clone()).unwrap();
+            assert_eq!(ptxt.get_len().unwrap(), N);
+            for (i, val) in input.iter().enumerate().step_by(97) {
+                let zz = ptxt.get_index(i).unwrap();
+                assert_eq!(zz.to_fieldelement::<ark_bn254::Fr>().unwrap(), *val);
+            }
+            assert_eq!(ptxt.coeffs::<ark_bn254::Fr>(2 * N).unwrap()[..N], input);
+            assert_eq!(ptxt.coeffs::<ark_bn254::Fr>(N - 1), Err(Error::BatchSlots));
+
+            // The characteristic itself cannot be read back as a field element
+            let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+            ptxt.set_index(0, &p).unwrap();
+            assert!(ptxt.coeffs::<ark_bn254::Fr>(N).is_err());
+        }
+    }
+
     #[test]
     fn packed_arithmetic_test() {
         const N: usize = 16384;
@@ -180,6 +285,102 @@ mod test {
         }
     }
 
+    #[test]
+    fn coefficient_arithmetic_test() {
+        let encoder = CoefficientEncoder::new(M as CLong);
+
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context = Context::build(M as CLong, &p, 700).unwrap();
+        let seckey = SecKey::build(&context).unwrap();
+        let pubkey = PubKey::from_seckey(&seckey).unwrap();
+
+        let mut rng = thread_rng();
+        for _ in 0..TESTRUNS {
+            let a: Vec<_> = (0..N).map(|_| ark_bn254::Fr::rand(&mut rng)).collect();
+            let b: Vec<_> = (0..N).map(|_| ark_bn254::Fr::rand(&mut rng)).collect();
+            let enc_a = EncodedPtxt::encode_coefficients(&a, &encoder).unwrap();
+            let enc_b = EncodedPtxt::encode_coefficients(&b, &encoder).unwrap();
+            let ctxt_a = pubkey.packed_encrypt(&enc_a).unwrap();
+            let ctxt_b = pubkey.packed_encrypt(&enc_b).unwrap();
+
+            let add = seckey
+                .packed_decrypt(&(&ctxt_a + &ctxt_b))
+                .unwrap()
+                .decode_coefficients(&encoder)
+                .unwrap();
+            let mul = seckey
+                .packed_decrypt(&(&ctxt_a * &ctxt_b))
+                .unwrap()
+                .decode_coefficients(&encoder)
+                .unwrap();
+
+            assert_eq!(
+                add,
+                a.iter()
+                    .zip(b.iter())
+                    .map(|(a, b)| a + b)
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(mul, negacyclic_naive_mult(&a, &b));
+        }
+    }
+
+    #[test]
+    fn cyclotomic_packed_arithmetic_test() {
+        // p = 1 mod 3393, so all phi(m) = 2016 slots hold elements of F_p
+        const M: CLong = 3393;
+
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context = Context::build(M, &p, 700).unwrap();
+        let encoder = CyclotomicEncoder::from_context(&context).unwrap();
+        let seckey = SecKey::build(&context).unwrap();
+        let pubkey = PubKey::from_seckey(&seckey).unwrap();
+
+        let mut rng = thread_rng();
+        for _ in 0..TESTRUNS {
+            let a: Vec<_> = (0..encoder.slot_count())
+                .map(|_| ark_bn254::Fr::rand(&mut rng))
+                .collect();
+            let b: Vec<_> = (0..encoder.slot_count())
+                .map(|_| ark_bn254::Fr::rand(&mut rng))
+                .collect();
+            let enc_a = EncodedPtxt::encode_cyclotomic(&a, &encoder).unwrap();
+            let enc_b = EncodedPtxt::encode_cyclotomic(&b, &encoder).unwrap();
+            assert_eq!(enc_a.decode_cyclotomic(&encoder).unwrap(), a);
+
+            let ctxt_a = pubkey.packed_encrypt(&enc_a).unwrap();
+            let ctxt_b = pubkey.packed_encrypt(&enc_b).unwrap();
+            let ctxt_add = &ctxt_a + &ctxt_b;
+            let ctxt_mul = &ctxt_a * &ctxt_b;
+
+            let add = seckey
+                .packed_decrypt(&ctxt_add)
+                .unwrap()
+                .decode_cyclotomic(&encoder)
+                .unwrap();
+            let mul = seckey
+                .packed_decrypt(&ctxt_mul)
+                .unwrap()
+                .decode_cyclotomic(&encoder)
+                .unwrap();
+
+            assert_eq!(
+                add,
+                a.iter()
+                    .zip(b.iter())
+                    .map(|(a, b)| a + b)
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(
+                mul,
+                a.iter()
+                    .zip(b.iter())
+                    .map(|(a, b)| a * b)
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+
     #[test]
     fn packed_arithmetic_inplace_test() {
         const N: usize = 16384;
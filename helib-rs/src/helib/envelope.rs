@@ -0,0 +1,122 @@
+use super::{error::Error, seckey::SecKey, serialization};
+use crate::Context;
+use argon2::Argon2;
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use rand::{thread_rng, RngCore};
+use zeroize::Zeroizing;
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+
+impl SecKey {
+    const ENVELOPE_MAGIC: &'static [u8; 4] = b"HEEV";
+
+    fn derive_envelope_key(
+        passphrase: &[u8],
+        salt: &[u8],
+    ) -> Result<Zeroizing<[u8; KEY_LEN]>, Error> {
+        let mut key = Zeroizing::new([0u8; KEY_LEN]);
+        Argon2::default()
+            .hash_password_into(passphrase, salt, key.as_mut())
+            .map_err(|e| Error::Other(format!("Key derivation failed: {}", e)))?;
+        Ok(key)
+    }
+
+    /// Exports the secret key encrypted under a passphrase. The key is derived with Argon2id and the export is encrypted with ChaCha20-Poly1305.
+    pub fn to_encrypted_bytes(&self, passphrase: &[u8]) -> Result<Vec<u8>, Error> {
+        let plain = self.to_bytes()?;
+
+        let mut rng = thread_rng();
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        rng.fill_bytes(&mut salt);
+        rng.fill_bytes(&mut nonce);
+
+        let key = Self::derive_envelope_key(passphrase, &salt)?;
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(key.as_ref()));
+
+        // The header is authenticated as well
+        let payload_len = SALT_LEN + NONCE_LEN + plain.len() + 16;
+        let mut buf =
+            serialization::write_header(Self::ENVELOPE_MAGIC, &self.fingerprint(), payload_len);
+        let encrypted = cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &plain,
+                    aad: &buf,
+                },
+            )
+            .map_err(|_| Error::Other("Encryption failed".to_string()))?;
+        buf.extend_from_slice(&salt);
+        buf.extend_from_slice(&nonce);
+        buf.extend_from_slice(&encrypted);
+        Ok(buf)
+    }
+
+    /// Imports a secret key produced by [`SecKey::to_encrypted_bytes`].
+    pub fn from_encrypted_bytes(
+        context: &Context,
+        buf: &[u8],
+        passphrase: &[u8],
+    ) -> Result<Self, Error> {
+        let payload =
+            serialization::read_header(Self::ENVELOPE_MAGIC, &context.fingerprint(), buf)?;
+        if payload.len() < SALT_LEN + NONCE_LEN {
+            return Err(Error::Serialization("Buffer too short".to_string()));
+        }
+        let header = &buf[..buf.len() - payload.len()];
+        let (salt, rest) = payload.split_at(SALT_LEN);
+        let (nonce, encrypted) = rest.split_at(NONCE_LEN);
+
+        let key = Self::derive_envelope_key(passphrase, salt)?;
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(key.as_ref()));
+        let plain = Zeroizing::new(
+            cipher
+                .decrypt(
+                    Nonce::from_slice(nonce),
+                    Payload {
+                        msg: encrypted,
+                        aad: header,
+                    },
+                )
+                .map_err(|_| {
+                    Error::Serialization("Wrong passphrase or corrupted envelope".to_string())
+                })?,
+        );
+        SecKey::from_bytes(context, &plain)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{PubKey, ZZ};
+    use ark_ff::UniformRand;
+
+    #[test]
+    fn seckey_envelope() {
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context = Context::build(32109, &p, 700).unwrap();
+        let seckey = SecKey::build(&context).unwrap();
+        let pubkey = PubKey::from_seckey(&seckey).unwrap();
+        let bytes = seckey.to_encrypted_bytes(b"correct horse").unwrap();
+
+        assert!(matches!(
+            SecKey::from_encrypted_bytes(&context, &bytes, b"battery staple"),
+            Err(Error::Serialization(_))
+        ));
+
+        let seckey_ = SecKey::from_encrypted_bytes(&context, &bytes, b"correct horse").unwrap();
+        let input = ark_bn254::Fr::rand(&mut thread_rng());
+        let ctxt = pubkey.encrypt_fieldelement(input).unwrap();
+        let decrypted = seckey_
+            .decrypt_fieldelement::<ark_bn254::Fr>(&ctxt)
+            .unwrap();
+        assert_eq!(decrypted, input);
+    }
+}
@@ -10,6 +10,33 @@ pub enum Error {
     /// Too few slots available
     #[error("Too few slots available")]
     BatchSlots,
+    /// Object belongs to a different context
+    #[error("Context mismatch")]
+    ContextMismatch,
+    /// Parameters rejected by this crate or HElib
+    #[error("Invalid parameters: {0}")]
+    InvalidParameters(String),
+    /// The ciphertext has no noise budget left and cannot be decrypted correctly
+    #[error("Noise budget exhausted")]
+    NoiseBudgetExhausted,
+    /// Exception thrown inside HElib or NTL
+    #[error("HElib exception: {0}")]
+    HElibException(String),
+    /// No key-switching matrix for a rotation by step
+    #[error("No Galois key for step {step}")]
+    MissingGaloisKey { step: i32 },
+    /// No key-switching matrix for the automorphism X -> X^element
+    #[error("No automorphism key for X -> X^{element}")]
+    MissingAutomorphismKey { element: usize },
+    /// The GaloisEngine holds no key-switching matrices at all
+    #[error("GaloisEngine holds no keys")]
+    NoGaloisKeys,
+    /// Integer which cannot be represented in the requested type, e.g., a field element
+    #[error("Out of range: {0}")]
+    OutOfRange(String),
+    /// Invalid or unsupported binary encoding
+    #[error("Serialization error: {0}")]
+    Serialization(String),
     /// Some other error has occured.
     #[error("Err: {0}")]
     Other(String),
@@ -28,10 +55,42 @@ impl From<&str> for Error {
 
 impl Error {
     pub(crate) fn error_from_return(ret: CLong) -> Result<(), Error> {
+        if ret == 0 {
+            return Ok(());
+        }
+        // Always consume the message, such that it cannot be attributed to a later call
+        let message = Self::last_message();
         match ret {
-            0 => Ok(()),
             0x80004003 => Err(Error::Pointer),
-            _ => Err(Error::Other("Error: Unknown Error".to_string())),
+            0x80070057 => Err(Error::InvalidParameters(
+                message.unwrap_or_else(|| "Invalid argument".to_string()),
+            )),
+            0x80040201 => Err(Error::NoiseBudgetExhausted),
+            0x80040202 => Err(Error::ContextMismatch),
+            0x80040204 => Err(Error::Serialization(
+                message.unwrap_or_else(|| "Invalid binary encoding".to_string()),
+            )),
+            0x80040205 => Err(Error::OutOfRange(
+                message.unwrap_or_else(|| "Integer out of range".to_string()),
+            )),
+            _ => Err(Error::HElibException(
+                message.unwrap_or_else(|| format!("Unknown error {ret:#x}")),
+            )),
+        }
+    }
+
+    // The C layer keeps the message of the last failed call per thread
+    fn last_message() -> Option<String> {
+        let mut len = 0;
+        let ret = unsafe { helib_bindings::ext_last_error_bytes(&mut len) };
+        if ret != 0 || len == 0 {
+            return None;
+        }
+        let mut buf = vec![0u8; len as usize];
+        let ret = unsafe { helib_bindings::ext_last_error(buf.as_mut_ptr(), len) };
+        if ret != 0 {
+            return None;
         }
+        Some(String::from_utf8_lossy(&buf).into_owned())
     }
 }
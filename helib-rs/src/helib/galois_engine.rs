@@ -1,18 +1,56 @@
-use super::{error::Error, CLong};
-use crate::{Ctxt, SecKey};
-use std::{ffi::c_void, ptr::null_mut};
+use super::{ctxt::CtxtOwner, error::Error, rng, serialization, CLong};
+use crate::{Context, Ctxt, SecKey};
+use rand::RngCore;
+use std::{collections::BTreeSet, ffi::c_void, ptr::null_mut};
+
+/// How [`GaloisEngine::rotate_ctxt`] composes a rotation for which no key was generated.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RotationStrategy {
+    /// Signed-digit decomposition of the step, which needs the fewest key-switches.
+    #[default]
+    FewestKeySwitches,
+    /// Evaluates up to four candidate decompositions, the signed-digit and the binary decomposition of the step and of its negative representative, and keeps the result with the largest remaining noise budget. Candidates which fail are skipped. Costs several rotations per call.
+    LowestNoise,
+}
 
 #[derive(Debug)]
 pub struct GaloisEngine {
     pub(crate) ptr: *mut c_void,
+    m: CLong,
+    steps: BTreeSet<i32>,
+    strategy: RotationStrategy,
+    // The key-switching matrices refer to the context they were generated under
+    context: Option<Context>,
 }
 
 impl GaloisEngine {
+    const MAGIC: &'static [u8; 4] = b"HEGK";
+
     pub fn build(m: CLong) -> Result<Self, Error> {
         let mut ptr = null_mut();
-        let ret = unsafe { helib_bindings::GK_build(&mut ptr, m) };
+        let ret = unsafe { helib_bindings::ext_GK_build(&mut ptr, m) };
         Error::error_from_return(ret)?;
-        Ok(Self { ptr })
+        Ok(Self {
+            ptr,
+            m,
+            steps: BTreeSet::new(),
+            strategy: RotationStrategy::default(),
+            context: None,
+        })
+    }
+
+    /// Builds an engine from a key bundle produced by [`GaloisEngine::to_bytes`], such that ciphertexts can be rotated without access to the secret key.
+    pub fn from_bytes(context: &Context, buf: &[u8]) -> Result<Self, Error> {
+        let mut engine = Self::build(context.params().m)?;
+        engine.add_keys_from_bytes(context, buf)?;
+        Ok(engine)
+    }
+
+    fn check_context(&self, context: &Context) -> Result<(), Error> {
+        match &self.context {
+            Some(ctx) if ctx.fingerprint() != context.fingerprint() => Err(Error::ContextMismatch),
+            _ => Ok(()),
+        }
     }
 
     pub fn destroy(&mut self) -> Result<(), Error> {
@@ -27,18 +65,358 @@ impl GaloisEngine {
     }
 
     pub fn generate_key_for_step(&mut self, secky: &SecKey, step: i32) -> Result<(), Error> {
-        let ret = unsafe { helib_bindings::GK_generate_step(self.ptr, secky.ptr, step) };
+        self.check_context(secky.context())?;
+        rng::ensure_thread_seeded()?;
+        let ret = unsafe { helib_bindings::ext_GK_generate_step(self.ptr, secky.ptr(), step) };
+        Error::error_from_return(ret)?;
+        self.steps.insert(step);
+        self.context = Some(secky.context().to_owned());
+        Ok(())
+    }
+
+    /// Like [`GaloisEngine::generate_key_for_step`], with the key-switching matrix drawn from `rng`. Afterwards the random stream of the thread is reseeded from OS entropy, see [`crate::seed_thread_rng`].
+    pub fn generate_key_for_step_with_rng<R: RngCore + ?Sized>(
+        &mut self,
+        secky: &SecKey,
+        step: i32,
+        rng: &mut R,
+    ) -> Result<(), Error> {
+        let _seed = rng::ScopedSeed::new(rng)?;
+        self.generate_key_for_step(secky, step)
+    }
+
+    /// Generates the keys for all `steps` the engine does not hold yet, e.g. the ones planned by [`crate::matrix::Bsgs::hoisted_galois_steps`]. Returns the number of generated keys.
+    pub fn generate_missing_keys<I: IntoIterator<Item = i32>>(
+        &mut self,
+        secky: &SecKey,
+        steps: I,
+    ) -> Result<usize, Error> {
+        let mut generated = 0;
+        for step in steps {
+            if !self.steps.contains(&step) {
+                self.generate_key_for_step(secky, step)?;
+                generated += 1;
+            }
+        }
+        Ok(generated)
+    }
+
+    /// Generates keys for the steps ±2^i only. Any other row rotation is then composed out of these by [`GaloisEngine::rotate_ctxt`], using O(log n) instead of O(n) keys.
+    pub fn generate_power_of_two_keys(&mut self, secky: &SecKey) -> Result<usize, Error> {
+        let steps = Self::power_of_two_steps(self.row_size());
+        self.generate_missing_keys(secky, steps)
+    }
+
+    /// Sets how rotations without a dedicated key are composed.
+    pub fn set_rotation_strategy(&mut self, strategy: RotationStrategy) {
+        self.strategy = strategy;
+    }
+
+    pub fn rotation_strategy(&self) -> RotationStrategy {
+        self.strategy
+    }
+
+    /// Whether a rotation by `step` needs exactly one key-switch, i.e., the engine holds a key for `step` or for a step rotating by the same amount.
+    pub fn has_key_for_step(&self, step: i32) -> bool {
+        self.available_step(step).is_some()
+    }
+
+    /// The steps the engine holds keys for, in ascending order.
+    pub fn available_steps(&self) -> impl Iterator<Item = i32> + '_ {
+        self.steps.iter().copied()
+    }
+
+    /// Estimated memory of one key-switching matrix in bytes, measured by the size of its binary encoding. All keys of an engine have the same size, so the engine needs about `available_steps().count()` times this. Fails with [`Error::NoGaloisKeys`] for an engine without keys.
+    pub fn key_size_estimate(&self) -> Result<usize, Error> {
+        let step = self.steps.first().ok_or(Error::NoGaloisKeys)?;
+        let mut len = 0;
+        let ret = unsafe { helib_bindings::GK_step_bytes(self.ptr, *step, &mut len) };
+        Error::error_from_return(ret)?;
+        Ok(len as usize)
+    }
+
+    /// Serializes the key-switching matrices of all generated steps into a key bundle.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let steps = self.steps.iter().copied().collect::<Vec<_>>();
+        self.to_bytes_for_steps(&steps)
+    }
+
+    /// Serializes the key-switching matrices of the given steps into a key bundle.
+    ///
+    /// Bundle layout (little endian): number of keys (u32), followed by step (i32) | length (u64) | key for each key.
+    pub fn to_bytes_for_steps(&self, steps: &[i32]) -> Result<Vec<u8>, Error> {
+        let fingerprint = self
+            .context
+            .as_ref()
+            .map(Context::fingerprint)
+            .ok_or(Error::NoGaloisKeys)?;
+
+        let mut payload = Vec::new();
+        payload.extend_from_slice(&(steps.len() as u32).to_le_bytes());
+        for step in steps {
+            if !self.steps.contains(step) {
+                return Err(Error::MissingGaloisKey { step: *step });
+            }
+            let mut len = 0;
+            let ret = unsafe { helib_bindings::GK_step_bytes(self.ptr, *step, &mut len) };
+            Error::error_from_return(ret)?;
+
+            payload.extend_from_slice(&step.to_le_bytes());
+            payload.extend_from_slice(&(len as u64).to_le_bytes());
+            let offset = payload.len();
+            payload.resize(offset + len as usize, 0);
+            let ret = unsafe {
+                helib_bindings::GK_step_to_bytes(
+                    self.ptr,
+                    *step,
+                    payload[offset..].as_mut_ptr(),
+                    len,
+                )
+            };
+            Error::error_from_return(ret)?;
+        }
+
+        let mut buf = serialization::write_header(Self::MAGIC, &fingerprint, payload.len());
+        buf.extend_from_slice(&payload);
+        Ok(buf)
+    }
+
+    /// Adds all keys of a key bundle produced by [`GaloisEngine::to_bytes`] to this engine.
+    pub fn add_keys_from_bytes(&mut self, context: &Context, buf: &[u8]) -> Result<(), Error> {
+        self.check_context(context)?;
+        let mut payload = serialization::read_header(Self::MAGIC, &context.fingerprint(), buf)?;
+
+        let num_keys = u32::from_le_bytes(Self::take::<4>(&mut payload)?);
+        for _ in 0..num_keys {
+            let step = i32::from_le_bytes(Self::take::<4>(&mut payload)?);
+            let len = u64::from_le_bytes(Self::take::<8>(&mut payload)?) as usize;
+            if payload.len() < len {
+                return Err(Error::Serialization("Buffer too short".to_string()));
+            }
+            let (key, rest) = payload.split_at(len);
+            let ret = unsafe {
+                helib_bindings::GK_step_from_bytes(
+                    self.ptr,
+                    context.ptr(),
+                    step,
+                    key.as_ptr(),
+                    len as CLong,
+                )
+            };
+            Error::error_from_return(ret)?;
+            self.steps.insert(step);
+            payload = rest;
+        }
+        if !payload.is_empty() {
+            return Err(Error::Serialization("Trailing bytes".to_string()));
+        }
+        self.context = Some(context.to_owned());
+        Ok(())
+    }
+
+    fn take<const N: usize>(buf: &mut &[u8]) -> Result<[u8; N], Error> {
+        if buf.len() < N {
+            return Err(Error::Serialization("Buffer too short".to_string()));
+        }
+        let (read, rest) = buf.split_at(N);
+        *buf = rest;
+        Ok(read.try_into().expect("Size is correct"))
+    }
+
+    // Number of slots rotated by a row rotation, i.e., slots / 2 for power-of-two cyclotomics
+    fn row_size(&self) -> i32 {
+        (self.m >> 2) as i32
+    }
+
+    fn power_of_two_steps(row_size: i32) -> Vec<i32> {
+        let mut steps = Vec::new();
+        let mut power = 1;
+        while power < row_size >> 1 {
+            steps.push(power);
+            steps.push(-power);
+            power <<= 1;
+        }
+        // Rotating by row_size / 2 in either direction is the same
+        if power < row_size {
+            steps.push(power);
+        }
+        steps
+    }
+
+    // Signed binary digits of x with no two adjacent non-zero digits, which minimizes their number
+    fn non_adjacent_form(mut x: i64) -> Vec<i64> {
+        let mut digits = Vec::new();
+        let mut power = 1;
+        while x != 0 {
+            if x & 1 == 1 {
+                let digit = 2 - x.rem_euclid(4);
+                digits.push(digit * power);
+                x -= digit;
+            }
+            x >>= 1;
+            power <<= 1;
+        }
+        digits
+    }
+
+    // Binary digits of x, all with the sign of x
+    fn binary_form(x: i64) -> Vec<i64> {
+        let magnitude = x.unsigned_abs();
+        (0..u64::BITS)
+            .filter(|i| magnitude >> i & 1 == 1)
+            .map(|i| x.signum() << i)
+            .collect()
+    }
+
+    // Decompositions of step into steps with powers of two magnitude, sorted by their number of
+    // key-switches. A rotation by a multiple of the row size is the identity, so both the positive
+    // and negative representative are candidates, each in signed-digit and in binary form.
+    fn decompositions(step: i32, row_size: i32) -> Vec<Vec<i32>> {
+        let row_size = row_size as i64;
+        let positive = (step as i64).rem_euclid(row_size);
+        let representatives = [positive, positive - row_size];
+        let forms = representatives
+            .map(Self::non_adjacent_form)
+            .into_iter()
+            .chain(representatives.map(Self::binary_form));
+
+        let mut candidates: Vec<Vec<i32>> = Vec::new();
+        for digits in forms {
+            let decomposition = digits
+                .into_iter()
+                .filter(|digit| digit.abs() != row_size)
+                .map(|digit| digit as i32)
+                .collect();
+            if !candidates.contains(&decomposition) {
+                candidates.push(decomposition);
+            }
+        }
+        // Stable, such that the signed-digit forms come first among equally long candidates
+        candidates.sort_by_key(Vec::len);
+        candidates
+    }
+
+    // A step with a key which rotates by the same amount as step
+    fn available_step(&self, step: i32) -> Option<i32> {
+        let row_size = self.row_size();
+        [step, step - row_size, step + row_size]
+            .into_iter()
+            .find(|s| self.steps.contains(s))
+    }
+
+    // The decompositions of step for which the engine holds all keys
+    fn composed_candidates(&self, step: i32) -> Vec<Vec<i32>> {
+        Self::decompositions(step, self.row_size())
+            .into_iter()
+            .filter_map(|decomposition| {
+                decomposition
+                    .into_iter()
+                    .map(|s| self.available_step(s))
+                    .collect::<Option<Vec<_>>>()
+            })
+            .collect()
+    }
+
+    /// Whether [`GaloisEngine::rotate_ctxt`] can rotate by `step`, with a dedicated key or composed out of the available ones.
+    pub(crate) fn can_rotate(&self, step: i32) -> bool {
+        if self.steps.contains(&step) {
+            return true;
+        }
+        step != 0 && (step % self.row_size() == 0 || !self.composed_candidates(step).is_empty())
+    }
+
+    fn rotate_ctxt_with_key(&self, ctxt: &mut Ctxt, step: i32) -> Result<(), Error> {
+        let ret = unsafe { helib_bindings::ext_GK_rotate(self.ptr, ctxt.ptr, step) };
         Error::error_from_return(ret)
     }
 
+    fn rotate_ctxt_composed(&self, ctxt: &mut Ctxt, steps: &[i32]) -> Result<(), Error> {
+        for step in steps {
+            self.rotate_ctxt_with_key(ctxt, *step)?;
+        }
+        Ok(())
+    }
+
+    /// Rotates the rows of `ctxt` by `step` (step 0 swaps the columns). Without a key for `step`, the rotation is composed out of available keys (e.g. the ones from [`GaloisEngine::generate_power_of_two_keys`]) according to the [`RotationStrategy`]. Fails with [`Error::MissingGaloisKey`] if this is not possible.
     pub fn rotate_ctxt(&self, ctxt: &mut Ctxt, step: i32) -> Result<(), Error> {
-        let ret = unsafe { helib_bindings::GK_rotate(self.ptr, ctxt.ptr, step) };
-        Error::error_from_return(ret)
+        if self.steps.contains(&step) {
+            return self.rotate_ctxt_with_key(ctxt, step);
+        }
+        // The column swap cannot be composed
+        if step == 0 {
+            return Err(Error::MissingGaloisKey { step });
+        }
+
+        if step % self.row_size() == 0 {
+            return Ok(());
+        }
+        let candidates = self.composed_candidates(step);
+
+        match (self.strategy, candidates.as_slice()) {
+            (_, []) => Err(Error::MissingGaloisKey { step }),
+            (RotationStrategy::FewestKeySwitches, [fewest, ..]) | (_, [fewest]) => {
+                self.rotate_ctxt_composed(ctxt, fewest)
+            }
+            (RotationStrategy::LowestNoise, candidates) => {
+                let mut best: Option<(CLong, Ctxt)> = None;
+                let mut error = None;
+                for candidate in candidates {
+                    let mut tmp = ctxt.ctxt_clone()?;
+                    let budget = match self
+                        .rotate_ctxt_composed(&mut tmp, candidate)
+                        .and_then(|_| tmp.noise_budget())
+                    {
+                        Ok(budget) => budget,
+                        Err(err) => {
+                            error = Some(err);
+                            continue;
+                        }
+                    };
+                    if best.as_ref().map_or(true, |(b, _)| budget > *b) {
+                        best = Some((budget, tmp));
+                    }
+                }
+                match best {
+                    Some((_, best)) => {
+                        *ctxt = best;
+                        Ok(())
+                    }
+                    // Only fails if every candidate failed
+                    None => Err(error.expect("At least one candidate")),
+                }
+            }
+        }
     }
 
     pub fn rotate_ctxt_columns(&self, ctxt: &mut Ctxt) -> Result<(), Error> {
         self.rotate_ctxt(ctxt, 0)
     }
+
+    /// Decomposes `ctxt` once for [`GaloisEngine::rotate_hoisted`], such that rotating it by many steps only pays for one decomposition.
+    pub fn hoist(&self, ctxt: &Ctxt) -> Result<HoistedCtxt, Error> {
+        self.check_context(ctxt.context())?;
+        let mut hoisted = HoistedCtxt {
+            ptr: null_mut(),
+            owner: ctxt.owner.clone(),
+        };
+        let ret = unsafe { helib_bindings::GK_hoist(&mut hoisted.ptr, ctxt.ptr) };
+        Error::error_from_return(ret)?;
+        Ok(hoisted)
+    }
+
+    /// Rotates a hoisted ciphertext by `step` into a new ciphertext. Composed rotations cannot reuse the decomposition, so this requires a key for `step`.
+    pub fn rotate_hoisted(&self, hoisted: &HoistedCtxt, step: i32) -> Result<Ctxt, Error> {
+        self.check_context(hoisted.context())?;
+        let key_step = self
+            .available_step(step)
+            .ok_or(Error::MissingGaloisKey { step })?;
+        let mut ctxt = Ctxt::empty_pointer(hoisted.owner.clone());
+        let ret = unsafe {
+            helib_bindings::GK_hoisted_rotate(&mut ctxt.ptr, self.ptr, hoisted.ptr, key_step)
+        };
+        Error::error_from_return(ret)?;
+        Ok(ctxt)
+    }
 }
 
 impl Drop for GaloisEngine {
@@ -47,12 +425,53 @@ impl Drop for GaloisEngine {
     }
 }
 
+// SAFETY: Keys are only added through `&mut self`. Rotations only read the key-switching
+// matrices, such that one engine can serve concurrent rotations of different ciphertexts.
+unsafe impl Send for GaloisEngine {}
+unsafe impl Sync for GaloisEngine {}
+
+/// A ciphertext together with its key-switching decomposition, see [`GaloisEngine::hoist`].
+#[derive(Debug)]
+pub struct HoistedCtxt {
+    ptr: *mut c_void,
+    // Keeps the key of the decomposed ciphertext alive
+    owner: CtxtOwner,
+}
+
+impl HoistedCtxt {
+    pub fn context(&self) -> &Context {
+        self.owner.context()
+    }
+
+    pub fn destroy(&mut self) -> Result<(), Error> {
+        if self.ptr.is_null() {
+            return Ok(());
+        }
+
+        let ret = unsafe { helib_bindings::GK_hoisted_destroy(self.ptr) };
+        Error::error_from_return(ret)?;
+        self.ptr = null_mut();
+        Ok(())
+    }
+}
+
+impl Drop for HoistedCtxt {
+    fn drop(&mut self) {
+        self.destroy().expect("HoistedCtxt destroy failed");
+    }
+}
+
+// SAFETY: The decomposition is only read after it is built, every rotation writes to a new ciphertext.
+unsafe impl Send for HoistedCtxt {}
+unsafe impl Sync for HoistedCtxt {}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::{encoding::rotate_plain, BatchEncoder, Context, EncodedPtxt, PubKey, ZZ};
     use ark_ff::UniformRand;
-    use rand::{thread_rng, Rng};
+    use rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
+    use std::sync::Arc;
 
     const TESTRUNS: usize = 10;
     const N: usize = 16384;
@@ -64,6 +483,260 @@ mod test {
         galois.destroy().unwrap(); // Is also called in drop
     }
 
+    #[test]
+    fn generate_missing_keys() {
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context = Context::build(M as CLong, &p, 700).unwrap();
+        let seckey = SecKey::build(&context).unwrap();
+
+        let mut galois = GaloisEngine::build(M as CLong).unwrap();
+        galois.generate_key_for_step(&seckey, 1).unwrap();
+        assert_eq!(
+            galois.generate_missing_keys(&seckey, [1, 2, -1]).unwrap(),
+            2
+        );
+        assert_eq!(galois.generate_missing_keys(&seckey, [2, -1]).unwrap(), 0);
+        assert_eq!(galois.steps, BTreeSet::from([-1, 1, 2]));
+    }
+
+    #[test]
+    fn generate_key_with_rng() {
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context = Context::build(M as CLong, &p, 700).unwrap();
+        let seckey = SecKey::build(&context).unwrap();
+
+        let key_bytes = |seed| {
+            let mut galois = GaloisEngine::build(M as CLong).unwrap();
+            galois
+                .generate_key_for_step_with_rng(&seckey, 1, &mut StdRng::seed_from_u64(seed))
+                .unwrap();
+            galois.to_bytes().unwrap()
+        };
+        assert_eq!(key_bytes(42), key_bytes(42));
+        assert_ne!(key_bytes(42), key_bytes(43));
+    }
+
+    #[test]
+    fn missing_key() {
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context = Context::build(M as CLong, &p, 700).unwrap();
+        let seckey = SecKey::build(&context).unwrap();
+        let pubkey = PubKey::from_seckey(&seckey).unwrap();
+        let mut ctxt = pubkey.encrypt(&ZZ::from_long(1).unwrap()).unwrap();
+
+        let mut galois = GaloisEngine::build(M as CLong).unwrap();
+        assert_eq!(galois.key_size_estimate(), Err(Error::NoGaloisKeys));
+        assert_eq!(galois.to_bytes(), Err(Error::NoGaloisKeys));
+        galois.generate_missing_keys(&seckey, [1, -2]).unwrap();
+        assert_eq!(galois.available_steps().collect::<Vec<_>>(), [-2, 1]);
+        assert!(galois.has_key_for_step(1));
+        assert!(galois.has_key_for_step(1 - (N as i32 >> 1)));
+        assert!(!galois.has_key_for_step(2));
+        assert!(galois.key_size_estimate().unwrap() > 0);
+
+        assert_eq!(
+            galois.rotate_ctxt(&mut ctxt, 0).unwrap_err(),
+            Error::MissingGaloisKey { step: 0 }
+        );
+        assert_eq!(
+            galois.rotate_ctxt(&mut ctxt, 7).unwrap_err(),
+            Error::MissingGaloisKey { step: 7 }
+        );
+        assert_eq!(
+            galois.to_bytes_for_steps(&[1, 3]).unwrap_err(),
+            Error::MissingGaloisKey { step: 3 }
+        );
+    }
+
+    #[test]
+    fn power_of_two_decomposition() {
+        let row_size = (N >> 1) as i32;
+        let steps = GaloisEngine::power_of_two_steps(row_size);
+        assert_eq!(steps.len(), 2 * row_size.ilog2() as usize - 1);
+
+        let mut rng = thread_rng();
+        for _ in 0..TESTRUNS * 10 {
+            let step = rng.gen_range(-row_size..row_size);
+            let x = rng.gen_range(-(1i64 << 40)..1i64 << 40);
+            let naf = GaloisEngine::non_adjacent_form(x);
+            assert_eq!(naf.iter().sum::<i64>(), x);
+            assert!(naf.iter().all(|d| d.unsigned_abs().is_power_of_two()));
+
+            let decompositions = GaloisEngine::decompositions(step, row_size);
+            assert!(!decompositions.is_empty());
+            for decomposition in decompositions.iter() {
+                let sum = decomposition.iter().sum::<i32>();
+                assert_eq!((sum - step).rem_euclid(row_size), 0);
+                assert!(decomposition
+                    .iter()
+                    .all(|d| steps.contains(d) || steps.contains(&(d + row_size))));
+            }
+            // Sorted by number of key-switches
+            assert!(decompositions.windows(2).all(|w| w[0].len() <= w[1].len()));
+        }
+
+        // row_size - 1 is a single rotation by -1
+        assert_eq!(
+            GaloisEngine::decompositions(row_size - 1, row_size)[0],
+            vec![-1]
+        );
+
+        // 7 = 8 - 1 = 1 + 2 + 4, binary forms are compared as well
+        assert_eq!(GaloisEngine::binary_form(-6), vec![-2, -4]);
+        let decompositions = GaloisEngine::decompositions(7, row_size);
+        assert!(decompositions.len() > 2);
+        assert_eq!(decompositions[0], vec![-1, 8]);
+        assert!(decompositions.contains(&vec![1, 2, 4]));
+    }
+
+    #[test]
+    fn rotate_with_power_of_two_keys() {
+        let batch_encoder = BatchEncoder::new(M as CLong);
+
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context = Context::build(M as CLong, &p, 700).unwrap();
+        let seckey = SecKey::build(&context).unwrap();
+        let pubkey = PubKey::from_seckey(&seckey).unwrap();
+        let mut galois = GaloisEngine::build(M as CLong).unwrap();
+        galois.generate_power_of_two_keys(&seckey).unwrap();
+
+        let mut rng = thread_rng();
+        let input: Vec<_> = (0..N).map(|_| ark_bn254::Fr::rand(&mut rng)).collect();
+        let encoded = EncodedPtxt::encode(&input, &batch_encoder).unwrap();
+        let ctxt = pubkey.packed_encrypt(&encoded).unwrap();
+
+        for strategy in [
+            RotationStrategy::FewestKeySwitches,
+            RotationStrategy::LowestNoise,
+        ] {
+            galois.set_rotation_strategy(strategy);
+            let step = rng.gen_range(-(N as i32 >> 1) + 1..N as i32 >> 1);
+            let mut rotated = ctxt.clone();
+            galois.rotate_ctxt(&mut rotated, step).unwrap();
+
+            let decrypted = seckey.packed_decrypt(&rotated).unwrap();
+            let decoded = decrypted.decode(&batch_encoder).unwrap();
+            assert_eq!(decoded, rotate_plain(&input, step));
+        }
+    }
+
+    #[test]
+    fn rotate_hoisted_test() {
+        let batch_encoder = BatchEncoder::new(M as CLong);
+
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context = Context::build(M as CLong, &p, 700).unwrap();
+        let seckey = SecKey::build(&context).unwrap();
+        let pubkey = PubKey::from_seckey(&seckey).unwrap();
+
+        let mut rng = thread_rng();
+        let mut steps = (0..TESTRUNS)
+            .map(|_| rng.gen_range(-(N as i32 >> 1) + 1..N as i32 >> 1))
+            .collect::<Vec<_>>();
+        steps.push(0);
+        let mut galois = GaloisEngine::build(M as CLong).unwrap();
+        galois
+            .generate_missing_keys(&seckey, steps.iter().copied())
+            .unwrap();
+
+        let input: Vec<_> = (0..N).map(|_| ark_bn254::Fr::rand(&mut rng)).collect();
+        let encoded = EncodedPtxt::encode(&input, &batch_encoder).unwrap();
+        let ctxt = pubkey.packed_encrypt(&encoded).unwrap();
+        let hoisted = galois.hoist(&ctxt).unwrap();
+
+        for step in steps {
+            let rotated = galois.rotate_hoisted(&hoisted, step).unwrap();
+            let decrypted = seckey.packed_decrypt(&rotated).unwrap();
+            let decoded = decrypted.decode(&batch_encoder).unwrap();
+            assert_eq!(decoded, rotate_plain(&input, step));
+        }
+
+        // Composed rotations are not supported
+        let missing = (1..N as i32 >> 1)
+            .find(|s| !galois.has_key_for_step(*s))
+            .unwrap();
+        assert_eq!(
+            galois.rotate_hoisted(&hoisted, missing).unwrap_err(),
+            Error::MissingGaloisKey { step: missing }
+        );
+    }
+
+    #[test]
+    fn rotate_hoisted_wrong_context() {
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context1 = Context::build(M as CLong, &p, 700).unwrap();
+        let context2 = Context::build(M as CLong, &p, 750).unwrap();
+        let seckey1 = SecKey::build(&context1).unwrap();
+        let seckey2 = SecKey::build(&context2).unwrap();
+
+        let mut galois = GaloisEngine::build(M as CLong).unwrap();
+        galois.generate_key_for_step(&seckey1, 1).unwrap();
+
+        // Hoisted by an engine without keys, which accepts any context
+        let ctxt = seckey2.encrypt(&ZZ::from_long(42).unwrap()).unwrap();
+        let hoisted = GaloisEngine::build(M as CLong)
+            .unwrap()
+            .hoist(&ctxt)
+            .unwrap();
+        assert_eq!(
+            galois.rotate_hoisted(&hoisted, 1).unwrap_err(),
+            Error::ContextMismatch
+        );
+    }
+
+    #[test]
+    fn galois_keys_serialize() {
+        let batch_encoder = BatchEncoder::new(M as CLong);
+
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context = Context::build(M as CLong, &p, 700).unwrap();
+        let seckey = SecKey::build(&context).unwrap();
+        let pubkey = PubKey::from_seckey(&seckey).unwrap();
+
+        let mut rng = thread_rng();
+        let steps = (0..TESTRUNS)
+            .map(|_| rng.gen_range(-(N as i32 >> 1) + 1..N as i32 >> 1))
+            .collect::<Vec<_>>();
+
+        // Client side
+        let mut client = GaloisEngine::build(M as CLong).unwrap();
+        for step in steps.iter() {
+            client.generate_key_for_step(&seckey, *step).unwrap();
+        }
+        let bundle = client.to_bytes().unwrap();
+        drop(client);
+
+        // Server side
+        let server = GaloisEngine::from_bytes(&context, &bundle).unwrap();
+
+        let mut input: Vec<_> = (0..N).map(|_| ark_bn254::Fr::rand(&mut rng)).collect();
+        let encoded = EncodedPtxt::encode(&input, &batch_encoder).unwrap();
+        let mut ctxt = pubkey.packed_encrypt(&encoded).unwrap();
+        for step in steps {
+            server.rotate_ctxt(&mut ctxt, step).unwrap();
+            input = rotate_plain(&input, step);
+        }
+        let decrypted = seckey.packed_decrypt(&ctxt).unwrap();
+        let decoded = decrypted.decode(&batch_encoder).unwrap();
+        assert_eq!(decoded, input);
+    }
+
+    #[test]
+    fn galois_keys_deserialize_wrong_context() {
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context1 = Context::build(M as CLong, &p, 700).unwrap();
+        let context2 = Context::build(M as CLong, &p, 750).unwrap();
+        let seckey = SecKey::build(&context1).unwrap();
+
+        let mut client = GaloisEngine::build(M as CLong).unwrap();
+        client.generate_key_for_step(&seckey, 1).unwrap();
+        let bundle = client.to_bytes().unwrap();
+        assert_eq!(
+            GaloisEngine::from_bytes(&context2, &bundle).unwrap_err(),
+            Error::ContextMismatch
+        );
+    }
+
     #[test]
     fn rotate_test() {
         let batch_encoder = BatchEncoder::new(M as CLong);
@@ -105,4 +778,72 @@ mod test {
         input = expected;
         assert_eq!(decoded, input);
     }
+
+    #[test]
+    fn wrappers_are_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Context>();
+        assert_send_sync::<Ctxt>();
+        assert_send_sync::<EncodedPtxt>();
+        assert_send_sync::<GaloisEngine>();
+        assert_send_sync::<HoistedCtxt>();
+        assert_send_sync::<PubKey>();
+        assert_send_sync::<SecKey>();
+        assert_send_sync::<ZZ>();
+    }
+
+    #[test]
+    fn concurrent_rotate_and_mul() {
+        const THREADS: usize = 4;
+        let batch_encoder = BatchEncoder::new(M as CLong);
+
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context = Context::build(M as CLong, &p, 700).unwrap();
+        let seckey = SecKey::build(&context).unwrap();
+        let pubkey = PubKey::from_seckey(&seckey).unwrap();
+
+        let mut rng = thread_rng();
+        let steps = (0..THREADS)
+            .map(|_| rng.gen_range(-(N as i32 >> 1) + 1..N as i32 >> 1))
+            .collect::<Vec<_>>();
+        let mut galois = GaloisEngine::build(M as CLong).unwrap();
+        for step in steps.iter() {
+            galois.generate_key_for_step(&seckey, *step).unwrap();
+        }
+        let galois = Arc::new(galois);
+
+        let input: Vec<_> = (0..N).map(|_| ark_bn254::Fr::rand(&mut rng)).collect();
+        let mul: Vec<_> = (0..N).map(|_| ark_bn254::Fr::rand(&mut rng)).collect();
+        let encoded = EncodedPtxt::encode(&input, &batch_encoder).unwrap();
+        let encoded_mul = EncodedPtxt::encode(&mul, &batch_encoder).unwrap();
+        let ctxt = pubkey.packed_encrypt(&encoded).unwrap();
+
+        // Shared engine, ciphertext and plaintext; each thread works on its own copy of the ciphertext
+        let results = std::thread::scope(|s| {
+            let handles = steps
+                .iter()
+                .map(|step| {
+                    let galois = Arc::clone(&galois);
+                    let ctxt = &ctxt;
+                    let encoded_mul = &encoded_mul;
+                    s.spawn(move || {
+                        let mut rotated = ctxt.ctxt_mul_by_packed_constant(encoded_mul).unwrap();
+                        galois.rotate_ctxt(&mut rotated, *step).unwrap();
+                        rotated
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|h| h.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        let product: Vec<_> = input.iter().zip(mul.iter()).map(|(a, b)| *a * b).collect();
+        for (result, step) in results.iter().zip(steps) {
+            let decrypted = seckey.packed_decrypt(result).unwrap();
+            let decoded = decrypted.decode(&batch_encoder).unwrap();
+            assert_eq!(decoded, rotate_plain(&product, step));
+        }
+    }
 }
@@ -1,10 +1,19 @@
+pub(crate) mod automorphism_engine;
 pub(crate) mod context;
 pub(crate) mod ctxt;
 pub(crate) mod encoded_ptxt;
+#[cfg(feature = "envelope")]
+pub(crate) mod envelope;
 pub(crate) mod error;
 pub(crate) mod galois_engine;
+pub(crate) mod param_search;
 pub(crate) mod pubkey;
+pub(crate) mod rng;
 pub(crate) mod seckey;
+pub(crate) mod seeded_ctxt;
+#[cfg(feature = "serde")]
+pub(crate) mod serde_impl;
+pub(crate) mod serialization;
 pub(crate) mod zz;
 
 pub type CLong = i64; // TODO better handling since some OS have i32?
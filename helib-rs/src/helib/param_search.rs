@@ -0,0 +1,200 @@
+use super::{
+    context::{Context, ContextParams},
+    error::Error,
+    CLong,
+};
+use crate::BatchEncoder;
+use ark_ff::PrimeField;
+
+/// How much computation the parameters found by [`ContextParams::search`] have to support.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NoiseRequirement {
+    /// Number of sequential ciphertext-ciphertext multiplications
+    Depth(usize),
+    /// Noise budget in bits of a fresh ciphertext, see [`crate::Ctxt::noise_budget`]
+    NoiseBudget(CLong),
+}
+
+impl ContextParams {
+    // Largest m the search considers, building contexts beyond it gets slow
+    const MAX_SEARCH_M: CLong = 1 << 17;
+    // Bits kept on top of the estimated noise budget
+    const SAFETY_MARGIN_BITS: CLong = 32;
+
+    /// Searches parameters with a power-of-two `m` such that the plaintext modulus is the characteristic of `F`, the ciphertexts provide at least `slots` slots, and `noise` is supported. The ciphertext modulus is estimated from the noise requirement, the security level of each candidate is checked by building its context with [`Context::security_level`]. Candidates below `min_security` are rejected, the remaining ones are returned ordered by increasing `m`.
+    ///
+    /// Only power-of-two `m` up to 2^17 are considered, i.e., the parameters of [`BatchEncoder`], whose m / 2 slots are all usable. Other cyclotomics, such as the ones of [`crate::CyclotomicEncoder`], are never returned, even if they would provide the slots with a smaller `m`. Fails with [`Error::InvalidParameters`] if the required `m` exceeds [`BatchEncoder::max_m`] or 2^17.
+    ///
+    /// Building a context per candidate dominates the running time, which reaches seconds for large `m`.
+    pub fn search<F: PrimeField>(
+        slots: usize,
+        noise: NoiseRequirement,
+        min_security: f64,
+    ) -> Result<Vec<Self>, Error> {
+        if slots == 0 {
+            return Err(Error::InvalidParameters(
+                "At least one slot is required".to_string(),
+            ));
+        }
+        let max_m = BatchEncoder::<F>::max_m().min(Self::MAX_SEARCH_M);
+        let mut m = Self::min_power_of_two_m(slots);
+        if m > max_m {
+            return Err(Error::InvalidParameters(format!(
+                "{slots} slots require m = {m}, but at most m = {max_m} is supported"
+            )));
+        }
+
+        let mut candidates = Vec::new();
+        while m <= max_m {
+            let params = Self::candidate::<F>(m, noise);
+            let context = Context::from_params(&params)?;
+            if context.security_level()? >= min_security {
+                candidates.push(params);
+            }
+            m <<= 1;
+        }
+        Ok(candidates)
+    }
+
+    // Parameters for m with the ciphertext modulus estimated from noise
+    fn candidate<F: PrimeField>(m: CLong, noise: NoiseRequirement) -> Self {
+        let bits = Self::estimate_bits(F::MODULUS_BIT_SIZE as CLong, m, noise);
+        Self::for_field::<F>(m, bits)
+    }
+
+    // A power-of-two m provides m / 2 slots
+    fn min_power_of_two_m(slots: usize) -> CLong {
+        (slots.next_power_of_two() << 1) as CLong
+    }
+
+    // Heuristic BGV noise estimate in the canonical embedding, see Gentry, Halevi and Smart,
+    // "Homomorphic Evaluation of the AES Circuit" (CRYPTO 2012), Section 3 and Appendix C. After
+    // modulus switching the noise of a ciphertext is about p * sqrt(phi(m)) times a small constant.
+    // Multiplying two such ciphertexts multiplies the noises, growing them by about
+    // p * sqrt(phi(m)) again, which the next modulus switch has to remove. A level therefore costs
+    // about log2(p) + log2(phi(m)) / 2 bits plus the constant factors. We charge log2(p) + log2(m),
+    // which leaves (log2(m) + 1) / 2 bits per level for the constants since phi(m) = m / 2 for
+    // power-of-two m, e.g., 7 bits for m = 2^13. A fresh ciphertext carries noise of the same
+    // order, hence the additional level.
+    fn estimate_bits(p_bits: CLong, m: CLong, noise: NoiseRequirement) -> CLong {
+        let bits_per_level = p_bits + m.ilog2() as CLong;
+        let budget = match noise {
+            NoiseRequirement::Depth(depth) => depth as CLong * bits_per_level,
+            NoiseRequirement::NoiseBudget(budget) => budget,
+        };
+        budget + bits_per_level + Self::SAFETY_MARGIN_BITS
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{EncodedPtxt, PubKey, SecKey};
+    use ark_ff::UniformRand;
+    use rand::thread_rng;
+
+    #[test]
+    fn estimate_bits_test() {
+        let p_bits = ark_bn254::Fr::MODULUS_BIT_SIZE as CLong;
+        let m = 1 << 13;
+        let fresh = ContextParams::estimate_bits(p_bits, m, NoiseRequirement::Depth(0));
+        let depth1 = ContextParams::estimate_bits(p_bits, m, NoiseRequirement::Depth(1));
+        let depth2 = ContextParams::estimate_bits(p_bits, m, NoiseRequirement::Depth(2));
+        assert!(fresh < depth1 && depth1 < depth2);
+        assert_eq!(depth2 - depth1, depth1 - fresh);
+        assert_eq!(
+            ContextParams::estimate_bits(p_bits, m, NoiseRequirement::NoiseBudget(depth1 - fresh)),
+            depth1
+        );
+
+        assert_eq!(ContextParams::min_power_of_two_m(4096), 8192);
+        assert_eq!(ContextParams::min_power_of_two_m(3000), 8192);
+    }
+
+    #[test]
+    fn search_unsupported_slots() {
+        // p - 1 of the BN254 base field is only divisible by 2
+        assert!(matches!(
+            ContextParams::search::<ark_bn254::Fq>(2, NoiseRequirement::Depth(1), 80.0),
+            Err(Error::InvalidParameters(_))
+        ));
+        assert!(matches!(
+            ContextParams::search::<ark_bn254::Fr>(0, NoiseRequirement::Depth(1), 80.0),
+            Err(Error::InvalidParameters(_))
+        ));
+    }
+
+    #[test]
+    fn smallest_candidate_supports_requirement() {
+        const SLOTS: usize = 4096;
+        const BUDGET: CLong = 100;
+        let mut rng = thread_rng();
+
+        let m = ContextParams::min_power_of_two_m(SLOTS);
+        let encoder = BatchEncoder::<ark_bn254::Fr>::new(m);
+
+        // A depth-1 product still decrypts correctly
+        let params = ContextParams::candidate::<ark_bn254::Fr>(m, NoiseRequirement::Depth(1));
+        let context = Context::from_params(&params).unwrap();
+        let seckey = SecKey::build(&context).unwrap();
+        let pubkey = PubKey::from_seckey(&seckey).unwrap();
+
+        let a = (0..SLOTS)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let b = (0..SLOTS)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let ctxt_a = pubkey
+            .packed_encrypt(&EncodedPtxt::encode(&a, &encoder).unwrap())
+            .unwrap();
+        let ctxt_b = pubkey
+            .packed_encrypt(&EncodedPtxt::encode(&b, &encoder).unwrap())
+            .unwrap();
+        let product = ctxt_a.ctxt_mul(&ctxt_b).unwrap();
+        assert!(product.noise_budget().unwrap() > 0);
+        let decrypted = seckey
+            .packed_decrypt(&product)
+            .unwrap()
+            .decode(&encoder)
+            .unwrap();
+        let expected = a
+            .iter()
+            .zip(b.iter())
+            .map(|(a, b)| *a * b)
+            .collect::<Vec<_>>();
+        assert_eq!(decrypted, expected);
+
+        // A fresh ciphertext provides the requested budget
+        let params =
+            ContextParams::candidate::<ark_bn254::Fr>(m, NoiseRequirement::NoiseBudget(BUDGET));
+        let context = Context::from_params(&params).unwrap();
+        let seckey = SecKey::build(&context).unwrap();
+        let pubkey = PubKey::from_seckey(&seckey).unwrap();
+        let ctxt = pubkey
+            .packed_encrypt(&EncodedPtxt::encode(&a, &encoder).unwrap())
+            .unwrap();
+        assert!(ctxt.noise_budget().unwrap() >= BUDGET);
+    }
+
+    #[test]
+    #[ignore] // Builds and measures one context per power-of-two m up to 2^17
+    fn search_test() {
+        const SLOTS: usize = 4096;
+        const MIN_SECURITY: f64 = 128.0;
+
+        let candidates =
+            ContextParams::search::<ark_bn254::Fr>(SLOTS, NoiseRequirement::Depth(1), MIN_SECURITY)
+                .unwrap();
+        assert!(!candidates.is_empty());
+        for params in candidates {
+            assert!(params.m as usize >> 1 >= SLOTS);
+            assert_eq!(
+                params,
+                ContextParams::for_field::<ark_bn254::Fr>(params.m, params.bits)
+            );
+            let context = Context::from_params(&params).unwrap();
+            assert!(context.security_level().unwrap() >= MIN_SECURITY);
+        }
+    }
+}
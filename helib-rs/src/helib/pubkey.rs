@@ -1,22 +1,24 @@
-use super::{ctxt::Ctxt, error::Error, seckey::SecKey};
-use crate::{EncodedPtxt, ZZ};
+use super::{
+    ctxt::{Ctxt, CtxtOwner},
+    error::Error,
+    rng,
+    seckey::SecKey,
+    serialization::{self, Fingerprint},
+    CLong,
+};
+use crate::{BatchEncoder, Context, EncodedPtxt, ZZ};
 use ark_ff::PrimeField;
-use std::{ffi::c_void, ptr::null_mut};
+use std::{ffi::c_void, ptr::null_mut, sync::Arc};
 
 #[derive(Debug)]
-pub struct PubKey {
+pub(crate) struct PubKeyInner {
     pub(crate) ptr: *mut c_void,
+    // The HElib key refers to its context, so it has to outlive the key
+    pub(crate) context: Context,
 }
 
-impl PubKey {
-    pub fn from_seckey(seckey: &SecKey) -> Result<Self, Error> {
-        let mut ptr = null_mut();
-        let ret = unsafe { helib_bindings::pubkey_from_seckey(&mut ptr, seckey.ptr) };
-        Error::error_from_return(ret)?;
-        Ok(Self { ptr })
-    }
-
-    pub fn destroy(&mut self) -> Result<(), Error> {
+impl PubKeyInner {
+    fn destroy(&mut self) -> Result<(), Error> {
         if self.ptr.is_null() {
             return Ok(());
         }
@@ -26,38 +28,134 @@ impl PubKey {
         self.ptr = null_mut();
         Ok(())
     }
+}
+
+impl Drop for PubKeyInner {
+    fn drop(&mut self) {
+        self.destroy().expect("PubKey destroy failed");
+    }
+}
+
+// SAFETY: The public key is not modified after it is built and encryption is const in HElib,
+// drawing randomness from NTL's thread-local random stream.
+unsafe impl Send for PubKeyInner {}
+unsafe impl Sync for PubKeyInner {}
+
+/// Ciphertexts encrypted with or loaded under the public key refer to it inside HElib and keep it alive.
+#[derive(Debug)]
+pub struct PubKey {
+    pub(crate) inner: Arc<PubKeyInner>,
+}
+
+impl PubKey {
+    const MAGIC: &'static [u8; 4] = b"HEPK";
+
+    fn from_ptr(ptr: *mut c_void, context: &Context) -> Self {
+        Self {
+            inner: Arc::new(PubKeyInner {
+                ptr,
+                context: context.to_owned(),
+            }),
+        }
+    }
+
+    pub fn from_seckey(seckey: &SecKey) -> Result<Self, Error> {
+        let mut ptr = null_mut();
+        let ret = unsafe { helib_bindings::ext_pubkey_from_seckey(&mut ptr, seckey.ptr()) };
+        Error::error_from_return(ret)?;
+        Ok(Self::from_ptr(ptr, seckey.context()))
+    }
+
+    pub(crate) fn ptr(&self) -> *mut c_void {
+        self.inner.ptr
+    }
+
+    pub fn context(&self) -> &Context {
+        &self.inner.context
+    }
+
+    pub(crate) fn fingerprint(&self) -> Fingerprint {
+        self.inner.context.fingerprint()
+    }
+
+    /// Exports the public key, including the key-switching matrices required for homomorphic multiplication.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut len = 0;
+        let ret = unsafe { helib_bindings::pubkey_bytes(self.ptr(), &mut len) };
+        Error::error_from_return(ret)?;
+
+        let mut buf = serialization::write_header(Self::MAGIC, &self.fingerprint(), len as usize);
+        let offset = buf.len();
+        buf.resize(offset + len as usize, 0);
+        let ret =
+            unsafe { helib_bindings::pubkey_to_bytes(self.ptr(), buf[offset..].as_mut_ptr(), len) };
+        Error::error_from_return(ret)?;
+        Ok(buf)
+    }
+
+    /// Imports a public key produced by [`PubKey::to_bytes`]. Fails with [`Error::ContextMismatch`] if the key belongs to a different context.
+    pub fn from_bytes(context: &Context, buf: &[u8]) -> Result<Self, Error> {
+        let payload = serialization::read_header(Self::MAGIC, &context.fingerprint(), buf)?;
+        let mut ptr = null_mut();
+        let ret = unsafe {
+            helib_bindings::pubkey_from_bytes(
+                &mut ptr,
+                context.ptr(),
+                payload.as_ptr(),
+                payload.len() as CLong,
+            )
+        };
+        Error::error_from_return(ret)?;
+        Ok(Self::from_ptr(ptr, context))
+    }
+
+    /// Destroys the HElib key. Fails if ciphertexts bound to it are still alive, in which case it is destroyed once the last of them is dropped.
+    pub fn destroy(&mut self) -> Result<(), Error> {
+        match Arc::get_mut(&mut self.inner) {
+            Some(inner) => inner.destroy(),
+            None => Err(Error::Other("PubKey is still in use".to_string())),
+        }
+    }
 
     pub fn encrypt(&self, zz: &ZZ) -> Result<Ctxt, Error> {
-        let mut ctxt = Ctxt::empty_pointer();
-        let ret = unsafe { helib_bindings::pubkey_encrypt(&mut ctxt.ptr, self.ptr, zz.ptr) };
+        rng::ensure_thread_seeded()?;
+        let mut ctxt = Ctxt::empty_pointer(CtxtOwner::PubKey(self.inner.clone()));
+        let ret = unsafe { helib_bindings::ext_pubkey_encrypt(&mut ctxt.ptr, self.ptr(), zz.ptr) };
         Error::error_from_return(ret)?;
         Ok(ctxt)
     }
 
     pub fn packed_encrypt(&self, ptxt: &EncodedPtxt) -> Result<Ctxt, Error> {
-        let mut ctxt = Ctxt::empty_pointer();
-        let ret =
-            unsafe { helib_bindings::pubkey_packed_encrypt(&mut ctxt.ptr, self.ptr, ptxt.ptr) };
+        rng::ensure_thread_seeded()?;
+        let mut ctxt = Ctxt::empty_pointer(CtxtOwner::PubKey(self.inner.clone()));
+        let ret = unsafe {
+            helib_bindings::ext_pubkey_packed_encrypt(&mut ctxt.ptr, self.ptr(), ptxt.ptr)
+        };
         Error::error_from_return(ret)?;
         Ok(ctxt)
     }
 
+    /// Encodes `vec` into the slots and encrypts it in one step, converting the coefficients in bulk. Fails with [`Error::BatchSlots`] if `batch_encoder` does not match the slot count of the context.
+    pub fn encode_and_encrypt<F: PrimeField>(
+        &self,
+        vec: &[F],
+        batch_encoder: &BatchEncoder<F>,
+    ) -> Result<Ctxt, Error> {
+        self.context().check_batch_encoder(batch_encoder)?;
+        let ptxt = EncodedPtxt::encode(vec, batch_encoder)?;
+        self.packed_encrypt(&ptxt)
+    }
+
     pub fn encrypt_fieldelement<F: PrimeField>(&self, field: F) -> Result<Ctxt, Error> {
         let zz = ZZ::from_fieldelement(field)?;
         self.encrypt(&zz)
     }
 }
 
-impl Drop for PubKey {
-    fn drop(&mut self) {
-        self.destroy().expect("PubKey destroy failed");
-    }
-}
-
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{helib::CLong, BatchEncoder, Context, ZZ};
+    use crate::{BatchEncoder, ZZ};
     use ark_ff::UniformRand;
     use rand::thread_rng;
 
@@ -72,6 +170,43 @@ mod test {
         pubkey.destroy().unwrap(); // Is also called in drop
     }
 
+    #[test]
+    fn pubkey_serialize() {
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context = Context::build(32109, &p, 700).unwrap();
+        let seckey = SecKey::build(&context).unwrap();
+        let pubkey = PubKey::from_seckey(&seckey).unwrap();
+        let bytes = pubkey.to_bytes().unwrap();
+        let pubkey_ = PubKey::from_bytes(&context, &bytes).unwrap();
+
+        let mut rng = thread_rng();
+        for _ in 0..TESTRUNS {
+            let a = ark_bn254::Fr::rand(&mut rng);
+            let b = ark_bn254::Fr::rand(&mut rng);
+            let ctxt_a = pubkey_.encrypt_fieldelement(a).unwrap();
+            let ctxt_b = pubkey_.encrypt_fieldelement(b).unwrap();
+            let ctxt_mul = &ctxt_a * &ctxt_b;
+            let decrypted = seckey
+                .decrypt_fieldelement::<ark_bn254::Fr>(&ctxt_mul)
+                .unwrap();
+            assert_eq!(decrypted, a * b);
+        }
+    }
+
+    #[test]
+    fn pubkey_deserialize_wrong_context() {
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context1 = Context::build(32109, &p, 700).unwrap();
+        let context2 = Context::build(32109, &p, 750).unwrap();
+        let seckey = SecKey::build(&context1).unwrap();
+        let pubkey = PubKey::from_seckey(&seckey).unwrap();
+        let bytes = pubkey.to_bytes().unwrap();
+        assert_eq!(
+            PubKey::from_bytes(&context2, &bytes).unwrap_err(),
+            Error::ContextMismatch
+        );
+    }
+
     #[test]
     fn pubkey_encrypt_decrypt() {
         let p = ZZ::char::<ark_bn254::Fr>().unwrap();
@@ -125,4 +260,47 @@ mod test {
             assert_eq!(input, output);
         }
     }
+
+    #[test]
+    fn pubkey_encode_and_encrypt() {
+        const N: usize = 16384;
+        const M: usize = 2 * N;
+        let batch_encoder = BatchEncoder::new(M as CLong);
+
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context = Context::build(M as CLong, &p, 700).unwrap();
+        let seckey = SecKey::build(&context).unwrap();
+        let pubkey = PubKey::from_seckey(&seckey).unwrap();
+
+        let mut rng = thread_rng();
+        for _ in 0..TESTRUNS {
+            let input: Vec<_> = (0..N).map(|_| ark_bn254::Fr::rand(&mut rng)).collect();
+            let ctxt = pubkey.encode_and_encrypt(&input, &batch_encoder).unwrap();
+            let output = seckey.decrypt_and_decode(&ctxt, &batch_encoder).unwrap();
+            assert_eq!(input, output);
+        }
+
+        let input: Vec<_> = (0..N / 2).map(|_| ark_bn254::Fr::rand(&mut rng)).collect();
+        let ctxt = pubkey.encode_and_encrypt(&input, &batch_encoder).unwrap();
+        let output = seckey.decrypt_and_decode(&ctxt, &batch_encoder).unwrap();
+        assert_eq!(input, output[..N / 2]);
+        // Same as going through an EncodedPtxt
+        let ptxt = seckey.packed_decrypt(&ctxt).unwrap();
+        assert_eq!(output, ptxt.decode(&batch_encoder).unwrap());
+
+        // The encoder has to match the slots of the context
+        let small_encoder = BatchEncoder::new(M as CLong / 2);
+        assert_eq!(
+            pubkey
+                .encode_and_encrypt(&input, &small_encoder)
+                .unwrap_err(),
+            Error::BatchSlots
+        );
+        assert_eq!(
+            seckey
+                .decrypt_and_decode(&ctxt, &small_encoder)
+                .unwrap_err(),
+            Error::BatchSlots
+        );
+    }
 }
@@ -0,0 +1,48 @@
+use super::{error::Error, CLong};
+use rand::{rngs::OsRng, RngCore};
+use std::cell::Cell;
+
+const THREAD_SEED_LEN: usize = 32;
+
+thread_local! {
+    static SEEDED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Seeds the random stream of the calling thread, from which HElib draws the randomness of key generation and encryption, with bytes drawn from `rng`. Seeding with a seeded RNG makes keys and ciphertexts reproducible, e.g., for test vectors and benchmarks.
+///
+/// Without it, each thread seeds its stream from OS entropy on the first key generation or encryption. The stream is thread-local, so threads spawned afterwards (e.g., by the `parallel` feature) are not affected. The seed stays in effect until the next call, in contrast to the `*_with_rng` functions, which only seed the stream for their own call.
+pub fn seed_thread_rng<R: RngCore + ?Sized>(rng: &mut R) -> Result<(), Error> {
+    let mut seed = [0u8; THREAD_SEED_LEN];
+    rng.fill_bytes(&mut seed);
+    let ret = unsafe { helib_bindings::ext_set_thread_seed(seed.as_ptr(), seed.len() as CLong) };
+    Error::error_from_return(ret)?;
+    SEEDED.with(|seeded| seeded.set(true));
+    Ok(())
+}
+
+// Called before HElib draws randomness, such that it does not depend on the default seed of NTL
+pub(crate) fn ensure_thread_seeded() -> Result<(), Error> {
+    if SEEDED.with(Cell::get) {
+        return Ok(());
+    }
+    seed_thread_rng(&mut OsRng)
+}
+
+/// Seeds the stream of the calling thread from an RNG for one call of a `*_with_rng` function. Dropping the guard reseeds the stream from OS entropy, such that the following calls are not reproducible.
+pub(crate) struct ScopedSeed(());
+
+impl ScopedSeed {
+    pub(crate) fn new<R: RngCore + ?Sized>(rng: &mut R) -> Result<Self, Error> {
+        seed_thread_rng(rng)?;
+        Ok(Self(()))
+    }
+}
+
+impl Drop for ScopedSeed {
+    fn drop(&mut self) {
+        if seed_thread_rng(&mut OsRng).is_err() {
+            // Reseed on the next key generation or encryption instead
+            SEEDED.with(|seeded| seeded.set(false));
+        }
+    }
+}
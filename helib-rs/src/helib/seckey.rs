@@ -1,30 +1,139 @@
-use super::{ctxt::Ctxt, error::Error, pubkey::PubKey};
-use crate::{Context, EncodedPtxt, ZZ};
-use ark_ff::PrimeField;
-use std::{ffi::c_void, ptr::null_mut};
+use super::{
+    ctxt::{Ctxt, CtxtOwner},
+    error::Error,
+    pubkey::PubKey,
+    rng,
+    seeded_ctxt::SeededCtxt,
+    serialization::{self, Fingerprint},
+    CLong,
+};
+use crate::{BatchEncoder, Context, EncodedPtxt, ZZ};
+use ark_ff::{BigInteger, PrimeField};
+use rand::{thread_rng, RngCore};
+use std::{ffi::c_void, ptr::null_mut, sync::Arc};
+use zeroize::Zeroizing;
 
 #[derive(Debug)]
-pub struct SecKey {
+pub(crate) struct SecKeyInner {
     pub(crate) ptr: *mut c_void,
+    // The HElib key refers to its context, so it has to outlive the key
+    pub(crate) context: Context,
+}
+
+impl SecKeyInner {
+    fn destroy(&mut self) -> Result<(), Error> {
+        if self.ptr.is_null() {
+            return Ok(());
+        }
+
+        let ret = unsafe { helib_bindings::seckey_destroy(self.ptr) };
+        Error::error_from_return(ret)?;
+        self.ptr = null_mut();
+        Ok(())
+    }
+}
+
+impl Drop for SecKeyInner {
+    fn drop(&mut self) {
+        self.destroy().expect("SecKey destroy failed");
+    }
+}
+
+// SAFETY: The secret key is not modified after keygen. Encryption and decryption are const in
+// HElib and draw randomness from NTL's thread-local random stream. Galois keys are stored in the
+// `GaloisEngine`, not in the secret key.
+unsafe impl Send for SecKeyInner {}
+unsafe impl Sync for SecKeyInner {}
+
+/// Ciphertexts encrypted with the secret key refer to it inside HElib and keep it alive.
+#[derive(Debug)]
+pub struct SecKey {
+    pub(crate) inner: Arc<SecKeyInner>,
 }
 
 impl SecKey {
+    const MAGIC: &'static [u8; 4] = b"HESK";
+
+    fn from_ptr(ptr: *mut c_void, context: &Context) -> Self {
+        Self {
+            inner: Arc::new(SecKeyInner {
+                ptr,
+                context: context.to_owned(),
+            }),
+        }
+    }
+
     pub fn build(context: &Context) -> Result<Self, Error> {
+        rng::ensure_thread_seeded()?;
         let mut ptr = null_mut();
-        let ret = unsafe { helib_bindings::seckey_build(&mut ptr, context.ptr) };
+        let ret = unsafe { helib_bindings::ext_seckey_build(&mut ptr, context.ptr()) };
         Error::error_from_return(ret)?;
-        Ok(Self { ptr })
+        Ok(Self::from_ptr(ptr, context))
+    }
+
+    /// Like [`SecKey::build`], with the key drawn from `rng`. Afterwards the random stream of the thread is reseeded from OS entropy, see [`crate::seed_thread_rng`].
+    pub fn build_with_rng<R: RngCore + ?Sized>(
+        context: &Context,
+        rng: &mut R,
+    ) -> Result<Self, Error> {
+        let _seed = rng::ScopedSeed::new(rng)?;
+        Self::build(context)
+    }
+
+    pub(crate) fn ptr(&self) -> *mut c_void {
+        self.inner.ptr
     }
 
+    pub fn context(&self) -> &Context {
+        &self.inner.context
+    }
+
+    pub(crate) fn fingerprint(&self) -> Fingerprint {
+        self.inner.context.fingerprint()
+    }
+
+    /// Destroys the HElib key. Fails if ciphertexts encrypted with it are still alive, in which case it is destroyed once the last of them is dropped.
     pub fn destroy(&mut self) -> Result<(), Error> {
-        if self.ptr.is_null() {
-            return Ok(());
+        match Arc::get_mut(&mut self.inner) {
+            Some(inner) => inner.destroy(),
+            None => Err(Error::Other("SecKey is still in use".to_string())),
         }
+    }
 
-        let ret = unsafe { helib_bindings::seckey_destroy(self.ptr) };
+    /// Exports the secret key, including the key-switching matrices generated with it. The returned buffer is zeroized on drop.
+    pub fn to_bytes(&self) -> Result<Zeroizing<Vec<u8>>, Error> {
+        let mut len = 0;
+        let ret = unsafe { helib_bindings::seckey_bytes(self.ptr(), &mut len) };
         Error::error_from_return(ret)?;
-        self.ptr = null_mut();
-        Ok(())
+
+        // Allocated with the final capacity, such that resizing does not leave copies of the key behind
+        let mut buf = Zeroizing::new(serialization::write_header(
+            Self::MAGIC,
+            &self.fingerprint(),
+            len as usize,
+        ));
+        let offset = buf.len();
+        buf.resize(offset + len as usize, 0);
+        let ret =
+            unsafe { helib_bindings::seckey_to_bytes(self.ptr(), buf[offset..].as_mut_ptr(), len) };
+        Error::error_from_return(ret)?;
+        Ok(buf)
+    }
+
+    /// Imports a secret key produced by [`SecKey::to_bytes`]. Fails with [`Error::ContextMismatch`] if the key belongs to a different context.
+    pub fn from_bytes(context: &Context, buf: &[u8]) -> Result<Self, Error> {
+        let payload = serialization::read_header(Self::MAGIC, &context.fingerprint(), buf)?;
+        let mut ptr = null_mut();
+        let ret = unsafe {
+            helib_bindings::seckey_from_bytes(
+                &mut ptr,
+                context.ptr(),
+                payload.as_ptr(),
+                payload.len() as CLong,
+            )
+        };
+        Error::error_from_return(ret)?;
+        Ok(Self::from_ptr(ptr, context))
     }
 
     pub fn get_public_key(&self) -> Result<PubKey, Error> {
@@ -32,35 +141,136 @@ impl SecKey {
     }
 
     pub fn encrypt(&self, zz: &ZZ) -> Result<Ctxt, Error> {
-        let mut ctxt = Ctxt::empty_pointer();
-        let ret = unsafe { helib_bindings::seckey_encrypt(&mut ctxt.ptr, self.ptr, zz.ptr) };
+        rng::ensure_thread_seeded()?;
+        let mut ctxt = Ctxt::empty_pointer(CtxtOwner::SecKey(self.inner.clone()));
+        let ret = unsafe { helib_bindings::ext_seckey_encrypt(&mut ctxt.ptr, self.ptr(), zz.ptr) };
         Error::error_from_return(ret)?;
         Ok(ctxt)
     }
 
     pub fn packed_encrypt(&self, ptxt: &EncodedPtxt) -> Result<Ctxt, Error> {
-        let mut ctxt = Ctxt::empty_pointer();
-        let ret =
-            unsafe { helib_bindings::seckey_packed_encrypt(&mut ctxt.ptr, self.ptr, ptxt.ptr) };
+        rng::ensure_thread_seeded()?;
+        let mut ctxt = Ctxt::empty_pointer(CtxtOwner::SecKey(self.inner.clone()));
+        let ret = unsafe {
+            helib_bindings::ext_seckey_packed_encrypt(&mut ctxt.ptr, self.ptr(), ptxt.ptr)
+        };
         Error::error_from_return(ret)?;
         Ok(ctxt)
     }
 
+    /// Like [`SecKey::packed_encrypt`], but the random part of the ciphertext is derived from a fresh seed, see [`SeededCtxt`].
+    pub fn packed_encrypt_seeded(&self, ptxt: &EncodedPtxt) -> Result<SeededCtxt, Error> {
+        rng::ensure_thread_seeded()?;
+        self.packed_encrypt_seeded_inner(ptxt, &mut thread_rng())
+    }
+
+    /// Like [`SecKey::packed_encrypt_seeded`], with the seed and the encryption noise drawn from `rng`. Afterwards the random stream of the thread is reseeded from OS entropy.
+    pub fn packed_encrypt_seeded_with_rng<R: RngCore + ?Sized>(
+        &self,
+        ptxt: &EncodedPtxt,
+        rng: &mut R,
+    ) -> Result<SeededCtxt, Error> {
+        let _seed = rng::ScopedSeed::new(rng)?;
+        self.packed_encrypt_seeded_inner(ptxt, rng)
+    }
+
+    fn packed_encrypt_seeded_inner<R: RngCore + ?Sized>(
+        &self,
+        ptxt: &EncodedPtxt,
+        rng: &mut R,
+    ) -> Result<SeededCtxt, Error> {
+        let mut seed = [0u8; SeededCtxt::SEED_LEN];
+        rng.fill_bytes(&mut seed);
+
+        let mut seeded = SeededCtxt::empty_pointer(seed, self.context());
+        let ret = unsafe {
+            helib_bindings::seeded_ctxt_encrypt(
+                &mut seeded.ptr,
+                self.ptr(),
+                ptxt.ptr,
+                seed.as_ptr(),
+                SeededCtxt::SEED_LEN as CLong,
+            )
+        };
+        Error::error_from_return(ret)?;
+        Ok(seeded)
+    }
+
+    // Decryption of a foreign ciphertext does not fail in HElib, it returns garbage
+    fn check_context(&self, ctxt: &Ctxt) -> Result<(), Error> {
+        if ctxt.fingerprint() != self.fingerprint() {
+            return Err(Error::ContextMismatch);
+        }
+        Ok(())
+    }
+
+    /// Does not check the noise budget of `ctxt`, see [`SecKey::decrypt_checked`].
     pub fn decrypt(&self, ctxt: &Ctxt) -> Result<ZZ, Error> {
+        self.check_context(ctxt)?;
+        let mut zz = ZZ::empty_pointer();
+        let ret = unsafe { helib_bindings::ext_seckey_decrypt(&mut zz.ptr, self.ptr(), ctxt.ptr) };
+        Error::error_from_return(ret)?;
+        Ok(zz)
+    }
+
+    /// Like [`SecKey::decrypt`], but fails with [`Error::NoiseBudgetExhausted`] if the noise budget of `ctxt` is used up, in which case the decryption is most likely wrong.
+    pub fn decrypt_checked(&self, ctxt: &Ctxt) -> Result<ZZ, Error> {
+        self.check_context(ctxt)?;
         let mut zz = ZZ::empty_pointer();
-        let ret = unsafe { helib_bindings::seckey_decrypt(&mut zz.ptr, self.ptr, ctxt.ptr) };
+        let ret = unsafe {
+            helib_bindings::ext_seckey_decrypt_checked(&mut zz.ptr, self.ptr(), ctxt.ptr)
+        };
         Error::error_from_return(ret)?;
         Ok(zz)
     }
 
+    /// Does not check the noise budget of `ctxt`, see [`SecKey::packed_decrypt_checked`].
     pub fn packed_decrypt(&self, ctxt: &Ctxt) -> Result<EncodedPtxt, Error> {
+        self.check_context(ctxt)?;
         let mut ptxt = EncodedPtxt::empty_pointer();
-        let ret =
-            unsafe { helib_bindings::seckey_packed_decrypt(&mut ptxt.ptr, self.ptr, ctxt.ptr) };
+        let ret = unsafe {
+            helib_bindings::ext_seckey_packed_decrypt(&mut ptxt.ptr, self.ptr(), ctxt.ptr)
+        };
         Error::error_from_return(ret)?;
         Ok(ptxt)
     }
 
+    /// Like [`SecKey::packed_decrypt`], but fails with [`Error::NoiseBudgetExhausted`] if the noise budget of `ctxt` is used up, in which case the decryption is most likely wrong.
+    pub fn packed_decrypt_checked(&self, ctxt: &Ctxt) -> Result<EncodedPtxt, Error> {
+        self.check_context(ctxt)?;
+        let mut ptxt = EncodedPtxt::empty_pointer();
+        let ret = unsafe {
+            helib_bindings::ext_seckey_packed_decrypt_checked(&mut ptxt.ptr, self.ptr(), ctxt.ptr)
+        };
+        Error::error_from_return(ret)?;
+        Ok(ptxt)
+    }
+
+    /// Decrypts `ctxt` and decodes its slots in one step. The plaintext coefficients are written straight into a limb buffer, no [`EncodedPtxt`] is created. Fails with [`Error::BatchSlots`] if `batch_encoder` does not match the slot count of the context. Does not check the noise budget of `ctxt`.
+    pub fn decrypt_and_decode<F: PrimeField>(
+        &self,
+        ctxt: &Ctxt,
+        batch_encoder: &BatchEncoder<F>,
+    ) -> Result<Vec<F>, Error> {
+        self.context().check_batch_encoder(batch_encoder)?;
+        self.check_context(ctxt)?;
+        let len = batch_encoder.slot_count();
+        let limbs_per_coeff = F::BigInt::NUM_LIMBS;
+        let mut limbs = vec![0u64; len * limbs_per_coeff];
+        let ret = unsafe {
+            helib_bindings::seckey_packed_decrypt_to_limbs(
+                self.ptr(),
+                ctxt.ptr,
+                limbs.as_mut_ptr(),
+                len as CLong,
+                limbs_per_coeff as CLong,
+            )
+        };
+        Error::error_from_return(ret)?;
+        let coeffs = EncodedPtxt::coeffs_from_limbs(&limbs)?;
+        Ok(batch_encoder.decode(&coeffs))
+    }
+
     pub fn encrypt_fieldelement<F: PrimeField>(&self, field: F) -> Result<Ctxt, Error> {
         let zz = ZZ::from_fieldelement(field)?;
         self.encrypt(&zz)
@@ -72,18 +282,12 @@ impl SecKey {
     }
 }
 
-impl Drop for SecKey {
-    fn drop(&mut self) {
-        self.destroy().expect("SecKey destroy failed");
-    }
-}
-
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{helib::CLong, BatchEncoder, ZZ};
+    use crate::{BatchEncoder, ZZ};
     use ark_ff::UniformRand;
-    use rand::thread_rng;
+    use rand::{rngs::StdRng, thread_rng, SeedableRng};
 
     const TESTRUNS: usize = 10;
 
@@ -103,6 +307,68 @@ mod test {
         let _pubkey = seckey.get_public_key().unwrap();
     }
 
+    #[test]
+    fn seckey_serialize() {
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context = Context::build(32109, &p, 700).unwrap();
+        let seckey = SecKey::build(&context).unwrap();
+        let pubkey = seckey.get_public_key().unwrap();
+        let bytes = seckey.to_bytes().unwrap();
+        let seckey_ = SecKey::from_bytes(&context, &bytes).unwrap();
+
+        let mut rng = thread_rng();
+        for _ in 0..TESTRUNS {
+            let input = ark_bn254::Fr::rand(&mut rng);
+            let ctxt = pubkey.encrypt_fieldelement(input).unwrap();
+            let decrypted = seckey_
+                .decrypt_fieldelement::<ark_bn254::Fr>(&ctxt)
+                .unwrap();
+            assert_eq!(decrypted, input);
+        }
+    }
+
+    #[test]
+    fn seckey_build_with_rng() {
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context = Context::build(32109, &p, 700).unwrap();
+        let seckey1 = SecKey::build_with_rng(&context, &mut StdRng::seed_from_u64(42)).unwrap();
+        let seckey2 = SecKey::build_with_rng(&context, &mut StdRng::seed_from_u64(42)).unwrap();
+        let seckey3 = SecKey::build_with_rng(&context, &mut StdRng::seed_from_u64(43)).unwrap();
+        assert_eq!(seckey1.to_bytes().unwrap(), seckey2.to_bytes().unwrap());
+        assert_ne!(seckey1.to_bytes().unwrap(), seckey3.to_bytes().unwrap());
+    }
+
+    #[test]
+    fn seckey_with_rng_does_not_leak_seed() {
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context = Context::build(32109, &p, 700).unwrap();
+        let zz = ZZ::from_long(42).unwrap();
+
+        // The same seeded call, each followed by a default encryption
+        let encrypt_after_seeded = || {
+            let seckey = SecKey::build_with_rng(&context, &mut StdRng::seed_from_u64(42)).unwrap();
+            let ctxt = seckey.encrypt(&zz).unwrap();
+            (seckey.to_bytes().unwrap(), ctxt.to_bytes().unwrap())
+        };
+        let (seckey1, ctxt1) = encrypt_after_seeded();
+        let (seckey2, ctxt2) = encrypt_after_seeded();
+        assert_eq!(seckey1, seckey2);
+        assert_ne!(ctxt1, ctxt2);
+    }
+
+    #[test]
+    fn seckey_deserialize_wrong_context() {
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context1 = Context::build(32109, &p, 700).unwrap();
+        let context2 = Context::build(32109, &p, 750).unwrap();
+        let seckey = SecKey::build(&context1).unwrap();
+        let bytes = seckey.to_bytes().unwrap();
+        assert_eq!(
+            SecKey::from_bytes(&context2, &bytes).unwrap_err(),
+            Error::ContextMismatch
+        );
+    }
+
     #[test]
     fn seckey_encrypt_decrypt() {
         let p = ZZ::char::<ark_bn254::Fr>().unwrap();
@@ -119,6 +385,43 @@ mod test {
         }
     }
 
+    #[test]
+    fn seckey_decrypt_checks() {
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context = Context::build(32109, &p, 700).unwrap();
+        let other_context = Context::build(32109, &p, 750).unwrap();
+        let seckey = SecKey::build(&context).unwrap();
+        let other_seckey = SecKey::build(&other_context).unwrap();
+
+        let mut rng = thread_rng();
+        let input = ark_bn254::Fr::rand(&mut rng);
+        let mut ctxt = seckey.encrypt_fieldelement(input).unwrap();
+        assert_eq!(
+            other_seckey.decrypt(&ctxt).unwrap_err(),
+            Error::ContextMismatch
+        );
+
+        // Square until the first ciphertext without noise budget, which has to be rejected
+        let mut expected = input;
+        for _ in 0..64 {
+            match seckey.decrypt_checked(&ctxt) {
+                Ok(zz) => assert_eq!(zz.to_fieldelement::<ark_bn254::Fr>().unwrap(), expected),
+                Err(err) => {
+                    assert_eq!(err, Error::NoiseBudgetExhausted);
+                    assert_eq!(
+                        seckey.packed_decrypt_checked(&ctxt).unwrap_err(),
+                        Error::NoiseBudgetExhausted
+                    );
+                    return;
+                }
+            }
+            let copy = ctxt.ctxt_clone().unwrap();
+            ctxt.ctxt_mul_inplace(&copy).unwrap();
+            expected *= expected;
+        }
+        panic!("The noise budget was never exhausted");
+    }
+
     #[test]
     fn seckey_encrypt_decrypt_fieldelement() {
         let p = ZZ::char::<ark_bn254::Fr>().unwrap();
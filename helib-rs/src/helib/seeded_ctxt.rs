@@ -0,0 +1,179 @@
+use super::{
+    ctxt::{Ctxt, CtxtOwner},
+    error::Error,
+    serialization, CLong,
+};
+use crate::{Context, PubKey};
+use std::{ffi::c_void, ptr::null_mut};
+
+/// A ciphertext produced by secret-key encryption (see [`crate::SecKey::packed_encrypt_seeded`]), where the uniformly random part is replaced by the seed it is derived from. Roughly half the size of a [`Ctxt`], use [`SeededCtxt::expand`] to compute on it.
+#[derive(Debug)]
+pub struct SeededCtxt {
+    pub(crate) ptr: *mut c_void,
+    pub(crate) seed: [u8; SeededCtxt::SEED_LEN],
+    // The HElib object refers to its context, so it has to outlive it
+    context: Context,
+}
+
+impl SeededCtxt {
+    const MAGIC: &'static [u8; 4] = b"HESC";
+    pub(crate) const SEED_LEN: usize = 32;
+
+    pub(crate) fn empty_pointer(seed: [u8; Self::SEED_LEN], context: &Context) -> Self {
+        Self {
+            ptr: null_mut(),
+            seed,
+            context: context.to_owned(),
+        }
+    }
+
+    pub fn destroy(&mut self) -> Result<(), Error> {
+        if self.ptr.is_null() {
+            return Ok(());
+        }
+
+        let ret = unsafe { helib_bindings::seeded_ctxt_destroy(self.ptr) };
+        Error::error_from_return(ret)?;
+        self.ptr = null_mut();
+        Ok(())
+    }
+
+    /// Rebuilds the full ciphertext under `pubkey`. Fails with [`Error::ContextMismatch`] if `pubkey` belongs to a different context.
+    pub fn expand(&self, pubkey: &PubKey) -> Result<Ctxt, Error> {
+        if self.context.fingerprint() != pubkey.fingerprint() {
+            return Err(Error::ContextMismatch);
+        }
+
+        let mut ctxt = Ctxt::empty_pointer(CtxtOwner::PubKey(pubkey.inner.clone()));
+        let ret = unsafe {
+            helib_bindings::seeded_ctxt_expand(
+                &mut ctxt.ptr,
+                self.ptr,
+                pubkey.ptr(),
+                self.seed.as_ptr(),
+                Self::SEED_LEN as CLong,
+            )
+        };
+        Error::error_from_return(ret)?;
+        Ok(ctxt)
+    }
+
+    // Serialization
+
+    /// Serializes the seeded ciphertext into the same versioned binary format as [`Ctxt::to_bytes`]. The payload is the seed followed by the non-random part of the ciphertext.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut len = 0;
+        let ret = unsafe { helib_bindings::seeded_ctxt_bytes(self.ptr, &mut len) };
+        Error::error_from_return(ret)?;
+
+        let mut buf = serialization::write_header(
+            Self::MAGIC,
+            &self.context.fingerprint(),
+            Self::SEED_LEN + len as usize,
+        );
+        buf.extend_from_slice(&self.seed);
+        let offset = buf.len();
+        buf.resize(offset + len as usize, 0);
+        let ret = unsafe {
+            helib_bindings::seeded_ctxt_to_bytes(self.ptr, buf[offset..].as_mut_ptr(), len)
+        };
+        Error::error_from_return(ret)?;
+        Ok(buf)
+    }
+
+    /// Deserializes a seeded ciphertext produced by [`SeededCtxt::to_bytes`]. Fails with [`Error::ContextMismatch`] if it was serialized under a different context.
+    pub fn from_bytes(context: &Context, buf: &[u8]) -> Result<Self, Error> {
+        let payload = serialization::read_header(Self::MAGIC, &context.fingerprint(), buf)?;
+        if payload.len() < Self::SEED_LEN {
+            return Err(Error::Serialization("Buffer too short".to_string()));
+        }
+        let (seed, payload) = payload.split_at(Self::SEED_LEN);
+
+        let mut seeded =
+            SeededCtxt::empty_pointer(seed.try_into().expect("Seed has correct length"), context);
+        let ret = unsafe {
+            helib_bindings::seeded_ctxt_from_bytes(
+                &mut seeded.ptr,
+                context.ptr(),
+                payload.as_ptr(),
+                payload.len() as CLong,
+            )
+        };
+        Error::error_from_return(ret)?;
+        Ok(seeded)
+    }
+}
+
+impl Drop for SeededCtxt {
+    fn drop(&mut self) {
+        self.destroy().expect("SeededCtxt destroy failed");
+    }
+}
+
+// SAFETY: The ciphertext part is owned exclusively by this wrapper and never modified after
+// encryption.
+unsafe impl Send for SeededCtxt {}
+unsafe impl Sync for SeededCtxt {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{BatchEncoder, EncodedPtxt, SecKey, ZZ};
+    use ark_ff::UniformRand;
+    use rand::thread_rng;
+
+    const N: usize = 1024;
+    const M: usize = 2 * N;
+
+    #[test]
+    fn seeded_ctxt_expand() {
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context = Context::build(M as CLong, &p, 700).unwrap();
+        let seckey = SecKey::build(&context).unwrap();
+        let pubkey = PubKey::from_seckey(&seckey).unwrap();
+        let batch_encoder = BatchEncoder::new(M as CLong);
+        let mut rng = thread_rng();
+
+        let input: Vec<_> = (0..N).map(|_| ark_bn254::Fr::rand(&mut rng)).collect();
+        let ptxt = EncodedPtxt::encode(&input, &batch_encoder).unwrap();
+        let seeded = seckey.packed_encrypt_seeded(&ptxt).unwrap();
+
+        let bytes = seeded.to_bytes().unwrap();
+        let full = seckey.packed_encrypt(&ptxt).unwrap();
+        assert!(bytes.len() < full.to_bytes().unwrap().len());
+
+        let seeded_ = SeededCtxt::from_bytes(&context, &bytes).unwrap();
+        let mut ctxt = seeded_.expand(&pubkey).unwrap();
+        // Keeps the noise bound of the secret-key encryption
+        assert_eq!(ctxt.noise_budget().unwrap(), full.noise_budget().unwrap());
+        ctxt += &ctxt.clone();
+
+        let decrypted = seckey.packed_decrypt(&ctxt).unwrap();
+        let decoded = decrypted.decode(&batch_encoder).unwrap();
+        let expected: Vec<_> = input.iter().map(|x| *x + x).collect();
+        assert_eq!(decoded, expected);
+    }
+
+    #[test]
+    fn seeded_ctxt_wrong_context() {
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context1 = Context::build(M as CLong, &p, 700).unwrap();
+        let context2 = Context::build(M as CLong, &p, 750).unwrap();
+        let seckey1 = SecKey::build(&context1).unwrap();
+        let seckey2 = SecKey::build(&context2).unwrap();
+        let pubkey2 = PubKey::from_seckey(&seckey2).unwrap();
+        let batch_encoder = BatchEncoder::new(M as CLong);
+        let mut rng = thread_rng();
+
+        let input: Vec<_> = (0..N).map(|_| ark_bn254::Fr::rand(&mut rng)).collect();
+        let ptxt = EncodedPtxt::encode(&input, &batch_encoder).unwrap();
+        let seeded = seckey1.packed_encrypt_seeded(&ptxt).unwrap();
+        assert_eq!(seeded.expand(&pubkey2).unwrap_err(), Error::ContextMismatch);
+
+        let bytes = seeded.to_bytes().unwrap();
+        assert_eq!(
+            SeededCtxt::from_bytes(&context2, &bytes).unwrap_err(),
+            Error::ContextMismatch
+        );
+    }
+}
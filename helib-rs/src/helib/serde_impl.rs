@@ -0,0 +1,257 @@
+use super::{
+    context::Context, ctxt::Ctxt, encoded_ptxt::EncodedPtxt, error::Error,
+    galois_engine::GaloisEngine, pubkey::PubKey, zz::ZZ,
+};
+use serde::{
+    de::{self, DeserializeSeed, Visitor},
+    ser::{self, SerializeSeq},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{cmp::Ordering, fmt};
+
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a byte array")
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(v)
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+fn deserialize_bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    deserializer.deserialize_byte_buf(BytesVisitor)
+}
+
+// ZZ
+
+// A sign byte (0 for non-negative, 1 for negative) followed by the little-endian magnitude, since
+// the NTL byte conversion only covers the absolute value
+fn zz_to_signed_bytes(zz: &ZZ) -> Result<Vec<u8>, Error> {
+    let magnitude = zz.to_le_bytes()?;
+    let mut bytes = Vec::with_capacity(1 + magnitude.len());
+    bytes.push((zz.sign()? == Ordering::Less) as u8);
+    bytes.extend(magnitude);
+    Ok(bytes)
+}
+
+fn zz_from_signed_bytes(bytes: &[u8]) -> Result<ZZ, Error> {
+    match bytes.split_first() {
+        Some((0, magnitude)) => ZZ::from_le_bytes(magnitude),
+        Some((1, magnitude)) => ZZ::from_long(0)?.zz_sub(&ZZ::from_le_bytes(magnitude)?),
+        _ => Err(Error::Serialization("Invalid ZZ sign byte".to_string())),
+    }
+}
+
+impl Serialize for ZZ {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let bytes = zz_to_signed_bytes(self).map_err(ser::Error::custom)?;
+        serializer.serialize_bytes(&bytes)
+    }
+}
+
+impl<'de> Deserialize<'de> for ZZ {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = deserialize_bytes(deserializer)?;
+        zz_from_signed_bytes(&bytes).map_err(de::Error::custom)
+    }
+}
+
+// EncodedPtxt
+
+struct ZZBytes(Vec<u8>);
+
+impl<'de> Deserialize<'de> for ZZBytes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_bytes(deserializer).map(ZZBytes)
+    }
+}
+
+impl Serialize for EncodedPtxt {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let len = self.get_len().map_err(ser::Error::custom)?;
+        let mut seq = serializer.serialize_seq(Some(len))?;
+        for i in 0..len {
+            let zz = self.get_index(i).map_err(ser::Error::custom)?;
+            seq.serialize_element(&zz)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for EncodedPtxt {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let coeffs = Vec::<ZZBytes>::deserialize(deserializer)?;
+        let mut ptxt = EncodedPtxt::from_len(coeffs.len()).map_err(de::Error::custom)?;
+        for (i, coeff) in coeffs.iter().enumerate() {
+            let zz = zz_from_signed_bytes(&coeff.0).map_err(de::Error::custom)?;
+            ptxt.set_index(i, &zz).map_err(de::Error::custom)?;
+        }
+        Ok(ptxt)
+    }
+}
+
+// Ctxt
+
+impl Serialize for Ctxt {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let bytes = self.to_bytes().map_err(ser::Error::custom)?;
+        serializer.serialize_bytes(&bytes)
+    }
+}
+
+/// Deserializes a [`Ctxt`] under the given context, see [`Ctxt::from_bytes`].
+pub struct CtxtSeed<'a>(pub &'a Context);
+
+impl<'de> DeserializeSeed<'de> for CtxtSeed<'_> {
+    type Value = Ctxt;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        let bytes = deserialize_bytes(deserializer)?;
+        Ctxt::from_bytes(self.0, &bytes).map_err(de::Error::custom)
+    }
+}
+
+// PubKey
+
+impl Serialize for PubKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let bytes = self.to_bytes().map_err(ser::Error::custom)?;
+        serializer.serialize_bytes(&bytes)
+    }
+}
+
+/// Deserializes a [`PubKey`] under the given context, see [`PubKey::from_bytes`].
+pub struct PubKeySeed<'a>(pub &'a Context);
+
+impl<'de> DeserializeSeed<'de> for PubKeySeed<'_> {
+    type Value = PubKey;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        let bytes = deserialize_bytes(deserializer)?;
+        PubKey::from_bytes(self.0, &bytes).map_err(de::Error::custom)
+    }
+}
+
+// GaloisEngine
+
+impl Serialize for GaloisEngine {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let bytes = self.to_bytes().map_err(ser::Error::custom)?;
+        serializer.serialize_bytes(&bytes)
+    }
+}
+
+/// Deserializes a Galois key bundle into a [`GaloisEngine`] under the given context, see [`GaloisEngine::from_bytes`].
+pub struct GaloisEngineSeed<'a>(pub &'a Context);
+
+impl<'de> DeserializeSeed<'de> for GaloisEngineSeed<'_> {
+    type Value = GaloisEngine;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        let bytes = deserialize_bytes(deserializer)?;
+        GaloisEngine::from_bytes(self.0, &bytes).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{helib::CLong, BatchEncoder, SecKey};
+    use ark_ff::UniformRand;
+    use bincode::Options;
+    use rand::thread_rng;
+
+    const TESTRUNS: usize = 10;
+    const N: usize = 1024;
+    const M: usize = 2 * N;
+
+    #[test]
+    fn zz_serde() {
+        let mut rng = thread_rng();
+        for _ in 0..TESTRUNS {
+            let input = ark_bn254::Fr::rand(&mut rng);
+            let zz = ZZ::from_fieldelement(input).unwrap();
+            let bytes = bincode::serialize(&zz).unwrap();
+            let zz_: ZZ = bincode::deserialize(&bytes).unwrap();
+            assert_eq!(zz_.to_fieldelement::<ark_bn254::Fr>().unwrap(), input);
+        }
+    }
+
+    #[test]
+    fn zz_serde_signed() {
+        for a in [-1234567890123, -1, 0, 1, 1234567890123] {
+            let zz = ZZ::from_long(a).unwrap();
+            let bytes = bincode::serialize(&zz).unwrap();
+            let zz_: ZZ = bincode::deserialize(&bytes).unwrap();
+            assert_eq!(zz_, zz);
+            assert_eq!(zz_.to_string(), a.to_string());
+        }
+        let invalid = bincode::serialize(&vec![2u8, 1]).unwrap();
+        assert!(bincode::deserialize::<ZZ>(&invalid).is_err());
+    }
+
+    #[test]
+    fn encoded_ptxt_serde() {
+        let batch_encoder = BatchEncoder::new(M as CLong);
+        let mut rng = thread_rng();
+        let input: Vec<_> = (0..N).map(|_| ark_bn254::Fr::rand(&mut rng)).collect();
+        let ptxt = EncodedPtxt::encode(&input, &batch_encoder).unwrap();
+        let bytes = bincode::serialize(&ptxt).unwrap();
+        let ptxt_: EncodedPtxt = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(ptxt_.decode(&batch_encoder).unwrap(), input);
+    }
+
+    #[test]
+    fn keys_and_ctxt_serde() {
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context = Context::build(M as CLong, &p, 700).unwrap();
+        let seckey = SecKey::build(&context).unwrap();
+        let pubkey = PubKey::from_seckey(&seckey).unwrap();
+        let mut galois = GaloisEngine::build(M as CLong).unwrap();
+        galois.generate_key_for_step(&seckey, 1).unwrap();
+        let options = bincode::DefaultOptions::new();
+
+        let bytes = options.serialize(&pubkey).unwrap();
+        let pubkey_ = options
+            .deserialize_seed(PubKeySeed(&context), &bytes)
+            .unwrap();
+
+        let bytes = options.serialize(&galois).unwrap();
+        let galois_ = options
+            .deserialize_seed(GaloisEngineSeed(&context), &bytes)
+            .unwrap();
+
+        let batch_encoder = BatchEncoder::new(M as CLong);
+        let mut rng = thread_rng();
+        let input: Vec<_> = (0..N).map(|_| ark_bn254::Fr::rand(&mut rng)).collect();
+        let ptxt = EncodedPtxt::encode(&input, &batch_encoder).unwrap();
+        let ctxt = pubkey_.packed_encrypt(&ptxt).unwrap();
+
+        let bytes = options.serialize(&ctxt).unwrap();
+        let mut ctxt_ = options
+            .deserialize_seed(CtxtSeed(&context), &bytes)
+            .unwrap();
+        galois_.rotate_ctxt(&mut ctxt_, 1).unwrap();
+
+        let decrypted = seckey.packed_decrypt(&ctxt_).unwrap();
+        let decoded = decrypted.decode(&batch_encoder).unwrap();
+        assert_eq!(decoded, crate::encoding::rotate_plain(&input, 1));
+    }
+}
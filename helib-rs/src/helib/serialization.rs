@@ -0,0 +1,113 @@
+use super::error::Error;
+
+/// Version of the binary format produced by the `to_bytes` functions of this crate.
+pub(crate) const FORMAT_VERSION: u16 = 1;
+pub(crate) const FINGERPRINT_LEN: usize = 32;
+const MAGIC_LEN: usize = 4;
+const HEADER_LEN: usize = MAGIC_LEN + size_of::<u16>() + FINGERPRINT_LEN + size_of::<u64>();
+
+/// Hash of the parameters a [`Context`](crate::Context) was built from.
+pub(crate) type Fingerprint = [u8; FINGERPRINT_LEN];
+
+/// Header layout (all integers little endian):
+/// magic (4 bytes) | format version (u16) | context fingerprint (32 bytes) | payload length (u64)
+pub(crate) fn write_header(
+    magic: &[u8; MAGIC_LEN],
+    fingerprint: &Fingerprint,
+    payload_len: usize,
+) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(HEADER_LEN + payload_len);
+    buf.extend_from_slice(magic);
+    buf.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    buf.extend_from_slice(fingerprint);
+    buf.extend_from_slice(&(payload_len as u64).to_le_bytes());
+    buf
+}
+
+/// Checks the header and returns the payload following it.
+pub(crate) fn read_header<'a>(
+    magic: &[u8; MAGIC_LEN],
+    fingerprint: &Fingerprint,
+    buf: &'a [u8],
+) -> Result<&'a [u8], Error> {
+    if buf.len() < HEADER_LEN {
+        return Err(Error::Serialization("Buffer too short".to_string()));
+    }
+    let (header, payload) = buf.split_at(HEADER_LEN);
+    let (read_magic, rest) = header.split_at(MAGIC_LEN);
+    let (read_version, rest) = rest.split_at(size_of::<u16>());
+    let (read_fingerprint, read_len) = rest.split_at(FINGERPRINT_LEN);
+
+    if read_magic != magic {
+        return Err(Error::Serialization("Invalid magic".to_string()));
+    }
+    let version = u16::from_le_bytes(read_version.try_into().expect("Size is correct"));
+    if version != FORMAT_VERSION {
+        return Err(Error::Serialization(format!(
+            "Unsupported format version {}",
+            version
+        )));
+    }
+    if read_fingerprint != fingerprint {
+        return Err(Error::ContextMismatch);
+    }
+    let len = u64::from_le_bytes(read_len.try_into().expect("Size is correct"));
+    if len != payload.len() as u64 {
+        return Err(Error::Serialization("Invalid payload length".to_string()));
+    }
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const MAGIC: &[u8; MAGIC_LEN] = b"TEST";
+
+    fn serialize(fingerprint: &Fingerprint, payload: &[u8]) -> Vec<u8> {
+        let mut buf = write_header(MAGIC, fingerprint, payload.len());
+        buf.extend_from_slice(payload);
+        buf
+    }
+
+    #[test]
+    fn header_roundtrip() {
+        let fingerprint = [1u8; FINGERPRINT_LEN];
+        let payload = [1, 2, 3, 4, 5];
+        let buf = serialize(&fingerprint, &payload);
+        assert_eq!(buf.len(), HEADER_LEN + payload.len());
+        let read = read_header(MAGIC, &fingerprint, &buf).unwrap();
+        assert_eq!(read, payload);
+    }
+
+    #[test]
+    fn header_errors() {
+        let fingerprint = [1u8; FINGERPRINT_LEN];
+        let payload = [1, 2, 3, 4, 5];
+        let buf = serialize(&fingerprint, &payload);
+
+        assert_eq!(
+            read_header(MAGIC, &[2u8; FINGERPRINT_LEN], &buf),
+            Err(Error::ContextMismatch)
+        );
+        assert!(matches!(
+            read_header(b"ABCD", &fingerprint, &buf),
+            Err(Error::Serialization(_))
+        ));
+        assert!(matches!(
+            read_header(MAGIC, &fingerprint, &buf[..buf.len() - 1]),
+            Err(Error::Serialization(_))
+        ));
+        assert!(matches!(
+            read_header(MAGIC, &fingerprint, &buf[..HEADER_LEN - 1]),
+            Err(Error::Serialization(_))
+        ));
+
+        let mut wrong_version = buf.clone();
+        wrong_version[MAGIC_LEN] += 1;
+        assert!(matches!(
+            read_header(MAGIC, &fingerprint, &wrong_version),
+            Err(Error::Serialization(_))
+        ));
+    }
+}
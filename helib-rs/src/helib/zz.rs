@@ -1,7 +1,14 @@
 use super::{error::Error, CLong};
 use ark_ff::PrimeField;
 use num_bigint::BigUint;
-use std::{ffi::c_void, ptr::null_mut};
+use std::{
+    cmp::Ordering,
+    ffi::c_void,
+    fmt,
+    ops::{Add, Mul, Rem, Sub},
+    ptr::null_mut,
+    str::FromStr,
+};
 
 #[derive(Debug)]
 pub struct ZZ {
@@ -16,28 +23,28 @@ impl ZZ {
     pub fn from_string(mut s: String) -> Result<Self, Error> {
         let mut ptr = null_mut();
         s.push('\0'); // Add null terminator to translate to C string
-        let ret = unsafe { helib_bindings::ZZ_from_string(&mut ptr, s.as_ptr() as *const i8) };
+        let ret = unsafe { helib_bindings::ext_ZZ_from_string(&mut ptr, s.as_ptr() as *const i8) };
         Error::error_from_return(ret)?;
         Ok(Self { ptr })
     }
 
     pub fn from_long(a: CLong) -> Result<Self, Error> {
         let mut ptr = null_mut();
-        let ret = unsafe { helib_bindings::ZZ_from_long(&mut ptr, a) };
+        let ret = unsafe { helib_bindings::ext_ZZ_from_long(&mut ptr, a) };
         Error::error_from_return(ret)?;
         Ok(Self { ptr })
     }
 
     pub fn zz_clone(&self) -> Result<ZZ, Error> {
         let mut zz = ZZ::empty_pointer();
-        let ret = unsafe { helib_bindings::ZZ_clone(&mut zz.ptr, self.ptr) };
+        let ret = unsafe { helib_bindings::ext_ZZ_clone(&mut zz.ptr, self.ptr) };
         Error::error_from_return(ret)?;
         Ok(zz)
     }
 
     pub fn num_bytes(&self) -> Result<CLong, Error> {
         let mut len = 0;
-        let ret = unsafe { helib_bindings::ZZ_bytes(self.ptr, &mut len) };
+        let ret = unsafe { helib_bindings::ext_ZZ_bytes(self.ptr, &mut len) };
         Error::error_from_return(ret)?;
         Ok(len)
     }
@@ -51,17 +58,25 @@ impl ZZ {
     }
 
     pub fn from_le_bytes(buf: &[u8]) -> Result<Self, Error> {
+        if buf.is_empty() {
+            return Self::from_long(0);
+        }
         let mut ptr = null_mut();
-        let ret =
-            unsafe { helib_bindings::ZZ_from_bytes(&mut ptr, buf.as_ptr(), buf.len() as CLong) };
+        let ret = unsafe {
+            helib_bindings::ext_ZZ_from_bytes(&mut ptr, buf.as_ptr(), buf.len() as CLong)
+        };
         Error::error_from_return(ret)?;
         Ok(Self { ptr })
     }
 
     pub fn to_le_bytes(&self) -> Result<Vec<u8>, Error> {
         let len = self.num_bytes()?;
+        // Zero has no bytes, so there is nothing to write
+        if len == 0 {
+            return Ok(Vec::new());
+        }
         let mut buf = vec![0u8; len as usize];
-        let ret = unsafe { helib_bindings::ZZ_to_bytes(self.ptr, buf.as_mut_ptr(), len) };
+        let ret = unsafe { helib_bindings::ext_ZZ_to_bytes(self.ptr, buf.as_mut_ptr(), len) };
         Error::error_from_return(ret)?;
         Ok(buf)
     }
@@ -79,7 +94,7 @@ impl ZZ {
 
     pub fn random_mod(mod_: &ZZ) -> Result<Self, Error> {
         let mut ptr = null_mut();
-        let ret = unsafe { helib_bindings::ZZ_random(&mut ptr, mod_.ptr) };
+        let ret = unsafe { helib_bindings::ext_ZZ_random(&mut ptr, mod_.ptr) };
         Error::error_from_return(ret)?;
         Ok(Self { ptr })
     }
@@ -89,8 +104,16 @@ impl ZZ {
         Self::from_le_bytes(&bytes)
     }
 
+    /// Writes the limbs of the canonical representative of `input` directly into the ZZ.
     pub fn from_fieldelement<F: PrimeField>(input: F) -> Result<Self, Error> {
-        Self::from_biguint(input.into())
+        let bigint = input.into_bigint();
+        let limbs = bigint.as_ref();
+        let mut ptr = null_mut();
+        let ret = unsafe {
+            helib_bindings::ZZ_from_limbs(&mut ptr, limbs.as_ptr(), limbs.len() as CLong)
+        };
+        Error::error_from_return(ret)?;
+        Ok(Self { ptr })
     }
 
     pub fn to_biguint(&self) -> Result<BigUint, Error> {
@@ -98,14 +121,191 @@ impl ZZ {
         Ok(BigUint::from_bytes_le(&bytes))
     }
 
+    /// Reads the ZZ back as field element. Fails with [`Error::OutOfRange`] if it is negative or not smaller than the characteristic of `F`, instead of reducing it.
     pub fn to_fieldelement<F: PrimeField>(&self) -> Result<F, Error> {
-        let biguint = self.to_biguint()?;
-        Ok(F::from(biguint))
+        let mut bigint = F::BigInt::default();
+        let limbs = bigint.as_mut();
+        let ret = unsafe {
+            helib_bindings::ZZ_to_limbs(self.ptr, limbs.as_mut_ptr(), limbs.len() as CLong)
+        };
+        Error::error_from_return(ret)?;
+        Self::fieldelement_from_bigint(bigint)
+    }
+
+    pub(crate) fn fieldelement_from_bigint<F: PrimeField>(bigint: F::BigInt) -> Result<F, Error> {
+        F::from_bigint(bigint)
+            .ok_or_else(|| Error::OutOfRange("Exceeds the field modulus".to_string()))
     }
 
     pub fn char<F: PrimeField>() -> Result<Self, Error> {
         Self::from_biguint(F::MODULUS.into())
     }
+
+    // Arithmetic
+
+    pub fn zz_add(&self, other: &ZZ) -> Result<ZZ, Error> {
+        let mut zz = ZZ::empty_pointer();
+        let ret = unsafe { helib_bindings::ZZ_add(&mut zz.ptr, self.ptr, other.ptr) };
+        Error::error_from_return(ret)?;
+        Ok(zz)
+    }
+
+    pub fn zz_sub(&self, other: &ZZ) -> Result<ZZ, Error> {
+        let mut zz = ZZ::empty_pointer();
+        let ret = unsafe { helib_bindings::ZZ_sub(&mut zz.ptr, self.ptr, other.ptr) };
+        Error::error_from_return(ret)?;
+        Ok(zz)
+    }
+
+    pub fn zz_mul(&self, other: &ZZ) -> Result<ZZ, Error> {
+        let mut zz = ZZ::empty_pointer();
+        let ret = unsafe { helib_bindings::ZZ_mul(&mut zz.ptr, self.ptr, other.ptr) };
+        Error::error_from_return(ret)?;
+        Ok(zz)
+    }
+
+    /// The remainder follows NTL and has the sign of `other`. Fails for `other` = 0.
+    pub fn zz_rem(&self, other: &ZZ) -> Result<ZZ, Error> {
+        let mut zz = ZZ::empty_pointer();
+        let ret = unsafe { helib_bindings::ZZ_rem(&mut zz.ptr, self.ptr, other.ptr) };
+        Error::error_from_return(ret)?;
+        Ok(zz)
+    }
+
+    /// self^exp mod modulus in [0, modulus). Fails for a negative `exp` or a non-positive `modulus`.
+    pub fn pow_mod(&self, exp: &ZZ, modulus: &ZZ) -> Result<ZZ, Error> {
+        let mut zz = ZZ::empty_pointer();
+        let ret = unsafe { helib_bindings::ZZ_powmod(&mut zz.ptr, self.ptr, exp.ptr, modulus.ptr) };
+        Error::error_from_return(ret)?;
+        Ok(zz)
+    }
+
+    pub fn zz_cmp(&self, other: &ZZ) -> Result<Ordering, Error> {
+        let mut cmp = 0;
+        let ret = unsafe { helib_bindings::ZZ_compare(self.ptr, other.ptr, &mut cmp) };
+        Error::error_from_return(ret)?;
+        Ok(cmp.cmp(&0))
+    }
+
+    /// Whether the ZZ is negative, zero or positive, as [`Ordering`] compared to zero.
+    pub fn sign(&self) -> Result<Ordering, Error> {
+        let mut sign = 0;
+        let ret = unsafe { helib_bindings::ZZ_sign(self.ptr, &mut sign) };
+        Error::error_from_return(ret)?;
+        Ok(sign.cmp(&0))
+    }
+}
+
+impl Add for &ZZ {
+    type Output = ZZ;
+
+    fn add(self, other: &ZZ) -> ZZ {
+        self.zz_add(other).expect("Add failed")
+    }
+}
+
+impl Sub for &ZZ {
+    type Output = ZZ;
+
+    fn sub(self, other: &ZZ) -> ZZ {
+        self.zz_sub(other).expect("Sub failed")
+    }
+}
+
+impl Mul for &ZZ {
+    type Output = ZZ;
+
+    fn mul(self, other: &ZZ) -> ZZ {
+        self.zz_mul(other).expect("Mul failed")
+    }
+}
+
+impl Rem for &ZZ {
+    type Output = ZZ;
+
+    /// See [`ZZ::zz_rem`] for the sign of the result.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if `other` is 0.
+    fn rem(self, other: &ZZ) -> ZZ {
+        if other.sign() == Ok(Ordering::Equal) {
+            panic!("attempt to calculate the remainder with a divisor of zero");
+        }
+        self.zz_rem(other).expect("Rem failed")
+    }
+}
+
+impl PartialEq for ZZ {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for ZZ {}
+
+impl PartialOrd for ZZ {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ZZ {
+    /// # Panics
+    ///
+    /// This function will panic if the comparison in NTL fails, see [`ZZ::zz_cmp`] for the fallible version.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.zz_cmp(other).expect("Compare failed")
+    }
+}
+
+impl fmt::Display for ZZ {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The byte encoding holds the absolute value
+        let abs = self.to_biguint().map_err(|_| fmt::Error)?;
+        if self.sign().map_err(|_| fmt::Error)? == Ordering::Less {
+            write!(f, "-{abs}")
+        } else {
+            write!(f, "{abs}")
+        }
+    }
+}
+
+impl FromStr for ZZ {
+    type Err = Error;
+
+    /// Parses a decimal integer with an optional leading `-`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s.strip_prefix('-').unwrap_or(s);
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(Error::Other(format!("Invalid integer: {s}")));
+        }
+        Self::from_string(s.to_owned())
+    }
+}
+
+impl From<u64> for ZZ {
+    /// # Panics
+    ///
+    /// This function will panic if NTL fails to allocate the integer.
+    fn from(value: u64) -> Self {
+        let limbs = [value];
+        let mut ptr = null_mut();
+        let ret = unsafe { helib_bindings::ZZ_from_limbs(&mut ptr, limbs.as_ptr(), 1) };
+        Error::error_from_return(ret).expect("ZZ from u64 failed");
+        Self { ptr }
+    }
+}
+
+impl From<BigUint> for ZZ {
+    /// See [`ZZ::from_biguint`] for the fallible version.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if NTL fails to allocate the integer.
+    fn from(value: BigUint) -> Self {
+        Self::from_biguint(value).expect("ZZ from BigUint failed")
+    }
 }
 
 impl Drop for ZZ {
@@ -114,6 +314,11 @@ impl Drop for ZZ {
     }
 }
 
+// SAFETY: NTL::ZZ is a plain heap-allocated integer owned by this wrapper. NTL only shares
+// state between threads through its (thread-local) random stream.
+unsafe impl Send for ZZ {}
+unsafe impl Sync for ZZ {}
+
 impl Clone for ZZ {
     fn clone(&self) -> Self {
         self.zz_clone().expect("ZZ clone failed")
@@ -124,6 +329,7 @@ impl Clone for ZZ {
 mod test {
     use super::*;
     use ark_ff::UniformRand;
+    use proptest::prelude::*;
     use rand::{thread_rng, Rng};
 
     const TESTRUNS: usize = 10;
@@ -139,6 +345,37 @@ mod test {
         }
     }
 
+    #[test]
+    fn to_primefield_out_of_range() {
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        assert!(matches!(
+            p.to_fieldelement::<ark_bn254::Fr>(),
+            Err(Error::OutOfRange(_))
+        ));
+        let p_minus_one = BigUint::from(ark_bn254::Fr::MODULUS) - 1u32;
+        let zz = ZZ::from_biguint(p_minus_one).unwrap();
+        assert_eq!(
+            zz.to_fieldelement::<ark_bn254::Fr>().unwrap(),
+            -ark_bn254::Fr::from(1u64)
+        );
+
+        // Does not fit into the limbs of the field
+        let zz = ZZ::from_biguint(BigUint::from(1u32) << 256).unwrap();
+        assert!(matches!(
+            zz.to_fieldelement::<ark_bn254::Fr>(),
+            Err(Error::OutOfRange(_))
+        ));
+        let zz = ZZ::from_long(-1).unwrap();
+        assert!(matches!(
+            zz.to_fieldelement::<ark_bn254::Fr>(),
+            Err(Error::OutOfRange(_))
+        ));
+        // The limbs match the byte encoding
+        let input = ark_bn254::Fr::from(u64::MAX) * ark_bn254::Fr::from(3u64);
+        let zz = ZZ::from_fieldelement(input).unwrap();
+        assert_eq!(zz.to_biguint().unwrap(), input.into());
+    }
+
     #[test]
     fn zz_from_long() {
         let mut rng = thread_rng();
@@ -191,4 +428,99 @@ mod test {
             assert_eq!(zz_, clone_);
         }
     }
+
+    fn biguint() -> impl Strategy<Value = BigUint> {
+        prop::collection::vec(any::<u32>(), 0..12).prop_map(BigUint::new)
+    }
+
+    fn nonzero_biguint() -> impl Strategy<Value = BigUint> {
+        biguint().prop_filter("nonzero", |x| *x != BigUint::ZERO)
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn zz_arithmetic_matches_biguint(a in biguint(), b in biguint()) {
+            let (zz_a, zz_b) = (ZZ::from(a.clone()), ZZ::from(b.clone()));
+            prop_assert_eq!((&zz_a + &zz_b).to_biguint().unwrap(), &a + &b);
+            prop_assert_eq!((&zz_a * &zz_b).to_biguint().unwrap(), &a * &b);
+            if a >= b {
+                prop_assert_eq!((&zz_a - &zz_b).to_biguint().unwrap(), &a - &b);
+            } else {
+                let diff = &zz_a - &zz_b;
+                prop_assert_eq!(diff.sign().unwrap(), Ordering::Less);
+                prop_assert_eq!(diff.to_string(), format!("-{}", &b - &a));
+            }
+            prop_assert_eq!(zz_a.cmp(&zz_b), a.cmp(&b));
+            prop_assert_eq!(zz_a == zz_b, a == b);
+        }
+
+        #[test]
+        fn zz_rem_pow_mod_matches_biguint(
+            a in biguint(),
+            exp in any::<u64>(),
+            modulus in nonzero_biguint(),
+        ) {
+            let zz_a = ZZ::from(a.clone());
+            let zz_modulus = ZZ::from(modulus.clone());
+            prop_assert_eq!((&zz_a % &zz_modulus).to_biguint().unwrap(), &a % &modulus);
+            let pow = zz_a.pow_mod(&ZZ::from(exp), &zz_modulus).unwrap();
+            prop_assert_eq!(pow.to_biguint().unwrap(), a.modpow(&exp.into(), &modulus));
+        }
+
+        #[test]
+        fn zz_display_from_str(a in biguint(), negative in any::<bool>()) {
+            let s = if negative && a != BigUint::ZERO {
+                format!("-{a}")
+            } else {
+                a.to_string()
+            };
+            let zz = ZZ::from_str(&s).unwrap();
+            prop_assert_eq!(zz.to_string(), s);
+            prop_assert_eq!(zz.to_biguint().unwrap(), a);
+        }
+
+        #[test]
+        fn zz_from_u64(a in any::<u64>()) {
+            prop_assert_eq!(ZZ::from(a).to_biguint().unwrap(), BigUint::from(a));
+            prop_assert_eq!(ZZ::from(a).to_string(), a.to_string());
+        }
+    }
+
+    #[test]
+    fn zz_invalid_arguments() {
+        let zero = ZZ::from(0u64);
+        let one = ZZ::from(1u64);
+        assert!(one.zz_rem(&zero).is_err());
+        assert!(one.pow_mod(&ZZ::from_long(-1).unwrap(), &one).is_err());
+        assert!(one.pow_mod(&one, &zero).is_err());
+        assert_eq!(ZZ::from(7u64).pow_mod(&one, &one).unwrap(), zero);
+        assert!(ZZ::from_str("12a").is_err());
+        assert!(ZZ::from_str("-").is_err());
+        assert!(ZZ::from_str("").is_err());
+    }
+
+    #[test]
+    fn zz_zero() {
+        let zero = ZZ::from(0u64);
+        assert!(zero.is_empty().unwrap());
+        assert!(zero.to_le_bytes().unwrap().is_empty());
+        assert_eq!(zero.to_biguint().unwrap(), BigUint::ZERO);
+        assert_eq!(zero.to_string(), "0");
+        assert_eq!(zero.sign().unwrap(), Ordering::Equal);
+        assert_eq!(ZZ::from_le_bytes(&[]).unwrap(), zero);
+        assert_eq!(ZZ::from(BigUint::ZERO), zero);
+        assert_eq!(&zero + &zero, zero);
+        assert_eq!(&zero - &zero, zero);
+        assert_eq!(&zero * &zero, zero);
+        assert_eq!(&zero % &ZZ::from(5u64), zero);
+        assert!(zero.zz_rem(&zero).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "divisor of zero")]
+    fn zz_rem_by_zero_panics() {
+        let _ = &ZZ::from(1u64) % &ZZ::from(0u64);
+    }
 }
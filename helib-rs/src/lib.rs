@@ -3,13 +3,22 @@ pub(crate) mod helib;
 pub mod matrix;
 
 pub use encoding::batch_encoder::BatchEncoder;
+pub use encoding::coefficient_encoder::CoefficientEncoder;
+pub use encoding::cyclotomic_encoder::{CyclotomicEncoder, SlotStructure};
 pub use encoding::ntt::NTTProcessor;
-pub use helib::context::Context;
+pub use encoding::plaintext_poly::PlaintextPoly;
+pub use helib::automorphism_engine::AutomorphismEngine;
+pub use helib::context::{Context, ContextParams};
 pub use helib::ctxt::Ctxt;
 pub use helib::encoded_ptxt::EncodedPtxt;
 pub use helib::error::Error;
-pub use helib::galois_engine::GaloisEngine;
+pub use helib::galois_engine::{GaloisEngine, HoistedCtxt, RotationStrategy};
+pub use helib::param_search::NoiseRequirement;
 pub use helib::pubkey::PubKey;
+pub use helib::rng::seed_thread_rng;
 pub use helib::seckey::SecKey;
+pub use helib::seeded_ctxt::SeededCtxt;
+#[cfg(feature = "serde")]
+pub use helib::serde_impl::{CtxtSeed, GaloisEngineSeed, PubKeySeed};
 pub use helib::zz::ZZ;
 pub use helib::CLong;
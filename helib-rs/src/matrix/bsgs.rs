@@ -4,8 +4,33 @@ use crate::{
     Ctxt, EncodedPtxt,
 };
 use ark_ff::PrimeField;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::collections::BTreeSet;
+
+use super::{EncodedFullyPackedMatrix, EncodedMatrix, MaybeSync, SquareMatrix};
+
+/// How a Bsgs operation packs the matrix-vector product into the slots of the ciphertexts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BsgsPacking {
+    /// [`Bsgs::babystep_giantstep`] (or the two-matrix variant) with the split of [`Bsgs::default_split`]. The dimension is at most a quarter or exactly half of the slots.
+    Packed,
+    /// [`Bsgs::fully_packed_bsgs`], the dimension equals the number of slots.
+    FullyPacked,
+    /// [`Bsgs::bsgs_multiple_of_packsize`], the dimension is a multiple of the number of slots.
+    MultipleOfPacksize,
+}
 
-use super::SquareMatrix;
+impl BsgsPacking {
+    /// The packing used for a `dim` x `dim` matrix on ciphertexts with `slots` slots.
+    pub fn for_dimension(dim: usize, slots: usize) -> Self {
+        match dim.cmp(&slots) {
+            std::cmp::Ordering::Less => Self::Packed,
+            std::cmp::Ordering::Equal => Self::FullyPacked,
+            std::cmp::Ordering::Greater => Self::MultipleOfPacksize,
+        }
+    }
+}
 
 pub struct Bsgs {}
 
@@ -28,9 +53,52 @@ impl Bsgs {
             ctxt.ctxt_add_inplace(&state_rot)?;
         }
 
-        let mut outer_sum = Ctxt::empty_pointer();
+        let rot = Self::baby_steps(ctxt, galois_engine, n1)?;
+
+        let inner_sums = Self::map_indices(n2, |k| {
+            let mut inner_sum = rot[0].ctxt_mul_by_packed_constant(&encoded_diags[k * n1])?;
+            for j in 1..n1 {
+                let tmp = rot[j].ctxt_mul_by_packed_constant(&encoded_diags[k * n1 + j])?;
+                inner_sum.ctxt_add_inplace(&tmp)?;
+            }
+            if k != 0 {
+                galois_engine.rotate_ctxt(&mut inner_sum, -((k * n1) as i32))?;
+            }
+            Ok(inner_sum)
+        })?;
+
+        *ctxt = Self::sum_in_order(inner_sums)?;
+        Ok(())
+    }
+
+    /// Whether the baby steps share one hoisted decomposition, which requires keys for all of them, see [`Bsgs::hoisted_galois_steps`].
+    pub(crate) fn hoists_baby_steps(galois_engine: &GaloisEngine, n1: usize) -> bool {
+        (1..n1).all(|j| galois_engine.has_key_for_step(-(j as i32)))
+    }
+
+    /// The rotations of `ctxt` by 0, -1, ..., -(n1 - 1). Computed from one hoisted decomposition if [`Bsgs::hoists_baby_steps`], otherwise as independent (composed) rotations if the engine can rotate by every baby step, e.g. with [`GaloisEngine::generate_power_of_two_keys`]. Both are evaluated by [`Bsgs::map_indices`]. Only the minimal key set of [`Bsgs::galois_steps`] falls back to a chain of -1 rotations, which stays sequential.
+    pub(crate) fn baby_steps(
+        ctxt: &Ctxt,
+        galois_engine: &GaloisEngine,
+        n1: usize,
+    ) -> Result<Vec<Ctxt>, Error> {
+        if Self::hoists_baby_steps(galois_engine, n1) {
+            let hoisted = galois_engine.hoist(ctxt)?;
+            return Self::map_indices(n1, |j| match j {
+                0 => ctxt.ctxt_clone(),
+                _ => galois_engine.rotate_hoisted(&hoisted, -(j as i32)),
+            });
+        }
+        if (1..n1).all(|j| galois_engine.can_rotate(-(j as i32))) {
+            return Self::map_indices(n1, |j| {
+                let mut rot = ctxt.ctxt_clone()?;
+                if j != 0 {
+                    galois_engine.rotate_ctxt(&mut rot, -(j as i32))?;
+                }
+                Ok(rot)
+            });
+        }
 
-        // prepare rotations
         let mut rot = Vec::with_capacity(n1);
         rot.push(ctxt.ctxt_clone()?);
         for j in 1..n1 {
@@ -38,26 +106,54 @@ impl Bsgs {
             galois_engine.rotate_ctxt(&mut tmp, -1)?;
             rot.push(tmp);
         }
+        Ok(rot)
+    }
 
-        for k in 0..n2 {
-            let mut inner_sum = rot[0].ctxt_mul_by_packed_constant(&encoded_diags[k * n1])?;
-            for j in 1..n1 {
-                let tmp = rot[j].ctxt_mul_by_packed_constant(&encoded_diags[k * n1 + j])?;
-                inner_sum.ctxt_add_inplace(&tmp)?;
-            }
+    /// Evaluates `f` on `0..n`, in parallel if the `parallel` feature is enabled. The results are returned in index order, such that both modes produce identical outputs.
+    pub(crate) fn map_indices<R, G>(n: usize, f: G) -> Result<Vec<R>, Error>
+    where
+        R: Send,
+        G: Fn(usize) -> Result<R, Error> + MaybeSync,
+    {
+        #[cfg(feature = "parallel")]
+        {
+            (0..n).into_par_iter().map(f).collect()
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            (0..n).map(f).collect()
+        }
+    }
 
-            if k == 0 {
-                outer_sum = inner_sum;
-            } else {
-                galois_engine.rotate_ctxt(&mut inner_sum, -((k * n1) as i32))?;
-                outer_sum.ctxt_add_inplace(&inner_sum)?;
-            }
+    /// Runs `a` and `b`, in parallel if the `parallel` feature is enabled.
+    pub(crate) fn join<RA, RB, A, B>(a: A, b: B) -> (RA, RB)
+    where
+        RA: Send,
+        RB: Send,
+        A: FnOnce() -> RA + MaybeSync,
+        B: FnOnce() -> RB + MaybeSync,
+    {
+        #[cfg(feature = "parallel")]
+        {
+            rayon::join(a, b)
         }
-        *ctxt = outer_sum;
-        Ok(())
+        #[cfg(not(feature = "parallel"))]
+        {
+            (a(), b())
+        }
+    }
+
+    // Always adds from the first to the last ciphertext, independent of the execution mode
+    fn sum_in_order(ctxts: Vec<Ctxt>) -> Result<Ctxt, Error> {
+        let mut iter = ctxts.into_iter();
+        let mut sum = iter.next().expect("At least one ciphertext");
+        for ctxt in iter {
+            sum.ctxt_add_inplace(&ctxt)?;
+        }
+        Ok(sum)
     }
 
-    pub fn babystep_giantstep<F: PrimeField, T: SquareMatrix<F>>(
+    pub fn babystep_giantstep<F: PrimeField, T: SquareMatrix<F> + MaybeSync>(
         ctxt: &mut Ctxt,
         matrix: &T,
         batch_encoder: &BatchEncoder<F>,
@@ -65,18 +161,11 @@ impl Bsgs {
         n1: usize,
         n2: usize,
     ) -> Result<(), Error> {
-        let encoded = Self::encode_one_matrix(matrix, batch_encoder, n1, n2)?;
-        Self::babystep_giantstep_inner(
-            ctxt,
-            &encoded,
-            galois_engine,
-            n1,
-            n2,
-            batch_encoder.slot_count(),
-        )
+        let encoded = EncodedMatrix::new(matrix, batch_encoder, n1, n2)?;
+        Self::babystep_giantstep_encoded(ctxt, &encoded, galois_engine)
     }
 
-    pub fn babystep_giantstep_two_matrices<F: PrimeField, T: SquareMatrix<F>>(
+    pub fn babystep_giantstep_two_matrices<F: PrimeField, T: SquareMatrix<F> + MaybeSync>(
         ctxt: &mut Ctxt,
         matrix1: &T,
         matrix2: &T,
@@ -85,164 +174,59 @@ impl Bsgs {
         n1: usize,
         n2: usize,
     ) -> Result<(), Error> {
-        let encoded = Self::encode_two_matrices(matrix1, matrix2, batch_encoder, n1, n2)?;
+        let encoded = EncodedMatrix::from_two_matrices(matrix1, matrix2, batch_encoder, n1, n2)?;
+        Self::babystep_giantstep_encoded(ctxt, &encoded, galois_engine)
+    }
+
+    /// Like [`Bsgs::babystep_giantstep`] or [`Bsgs::babystep_giantstep_two_matrices`], depending on how `matrix` was encoded, but without encoding the diagonals again.
+    pub fn babystep_giantstep_encoded(
+        ctxt: &mut Ctxt,
+        matrix: &EncodedMatrix,
+        galois_engine: &GaloisEngine,
+    ) -> Result<(), Error> {
         Self::babystep_giantstep_inner(
             ctxt,
-            &encoded,
+            &matrix.diags,
             galois_engine,
-            n1,
-            n2,
-            batch_encoder.slot_count(),
+            matrix.n1,
+            matrix.n2,
+            matrix.slots,
         )
     }
 
-    fn encode_one_matrix<F: PrimeField, T: SquareMatrix<F>>(
+    pub fn fully_packed_bsgs<F: PrimeField, T: SquareMatrix<F> + MaybeSync>(
+        ctxt: &mut Ctxt,
         matrix: &T,
         batch_encoder: &BatchEncoder<F>,
-        n1: usize,
-        n2: usize,
-    ) -> Result<Vec<EncodedPtxt>, Error> {
-        let dim = n1 * n2;
-        assert!(dim <= matrix.dimension());
-        let slots = batch_encoder.slot_count();
-        let halfslots = slots >> 1;
-        assert!(dim << 1 == slots || dim << 2 <= slots);
-        assert_eq!(dim, n1 * n2);
-
-        let mut encoded = Vec::with_capacity(dim);
-
-        for i in 0..dim {
-            let k = i / n1;
-            let mut diag = Vec::with_capacity(halfslots);
-
-            for j in 0..dim {
-                diag.push(matrix.get(j, (j + dim - i) % dim));
-            }
-            // rotate:
-            if k != 0 {
-                diag.rotate_left(k * n1);
-            }
-            // prepare for non-full-packed rotations
-            if slots != dim << 1 {
-                diag.resize(halfslots, F::zero());
-                for index in 0..k * n1 {
-                    let index_src = dim - 1 - index;
-                    let index_des = halfslots - 1 - index;
-                    diag[index_des] = diag[index_src];
-                    diag[index_src] = F::zero();
-                }
-            }
-            let enc = EncodedPtxt::encode(&diag, batch_encoder)?;
-            encoded.push(enc);
-        }
-        Ok(encoded)
-    }
-
-    fn encode_two_matrices<F: PrimeField, T: SquareMatrix<F>>(
-        matrix1: &T,
-        matrix2: &T,
-        batch_encoder: &BatchEncoder<F>,
-        n1: usize,
-        n2: usize,
-    ) -> Result<Vec<EncodedPtxt>, Error> {
-        let dim = n1 * n2;
-        assert!(dim <= matrix1.dimension());
-        assert!(dim <= matrix2.dimension());
-        let slots = batch_encoder.slot_count();
-        let halfslots = slots >> 1;
-        assert!(dim << 1 == slots || dim << 2 <= slots);
-
-        let mut encoded = Vec::with_capacity(dim);
-
-        for i in 0..dim {
-            let k = i / n1;
-            let mut diag = Vec::with_capacity(slots);
-            let mut tmp = Vec::with_capacity(dim);
-
-            for j in 0..dim {
-                diag.push(matrix1.get(j, (j + dim - i) % dim));
-                tmp.push(matrix2.get(j, (j + dim - i) % dim));
-            }
-            // rotate:
-            if k != 0 {
-                diag.rotate_left(k * n1);
-                tmp.rotate_left(k * n1);
-            }
-            // prepare for non-full-packed rotations
-            if slots != dim << 1 {
-                diag.resize(halfslots, F::zero());
-                tmp.resize(halfslots, F::zero());
-                for index in 0..k * n1 {
-                    let index_src = dim - 1 - index;
-                    let index_des = halfslots - 1 - index;
-                    diag[index_des] = diag[index_src];
-                    tmp[index_des] = tmp[index_src];
-                    diag[index_src] = F::zero();
-                    tmp[index_src] = F::zero();
-                }
-            }
-            diag.resize(slots, F::zero());
-            diag[halfslots..slots].copy_from_slice(&tmp[..(slots - halfslots)]);
-            let enc = EncodedPtxt::encode(&diag, batch_encoder)?;
-            encoded.push(enc);
-        }
-        Ok(encoded)
+        galois_engine: &GaloisEngine,
+    ) -> Result<(), Error> {
+        let encoded = EncodedFullyPackedMatrix::new(matrix, batch_encoder)?;
+        Self::fully_packed_bsgs_encoded(ctxt, &encoded, galois_engine)
     }
 
-    pub fn fully_packed_bsgs<F: PrimeField, T: SquareMatrix<F>>(
+    /// Like [`Bsgs::fully_packed_bsgs`], but without encoding the diagonals again.
+    pub fn fully_packed_bsgs_encoded(
         ctxt: &mut Ctxt,
-        matrix: &T,
-        batch_encoder: &BatchEncoder<F>,
+        matrix: &EncodedFullyPackedMatrix,
         galois_engine: &GaloisEngine,
     ) -> Result<(), Error> {
-        let dim = batch_encoder.slot_count();
-        let dim_half = dim >> 1;
-        let n2 = 1 << (dim_half.ilog2() >> 1);
-        let n1 = dim_half / n2;
-
-        let current_col_offset = matrix.get_col_offset();
-        let current_row_offset = matrix.get_row_offset();
-
         // Strategy: Split M = [M1, M2] [M3, M4] and v = [v1, v2], then result r = [r1, r2]  is computed as (M1*v1 + M2*v2, M3*v1 + M4*v2)
         let mut ctxt2 = ctxt.ctxt_clone()?;
 
-        // First half: M1*v1 + M4*v2
-        let mat1 = matrix.clone();
-        let mut mat4 = matrix.clone();
-        mat4.set_col_offset(current_col_offset + dim_half);
-        mat4.set_row_offset(current_row_offset + dim_half);
-        Self::babystep_giantstep_two_matrices(
-            ctxt,
-            &mat1,
-            &mat4,
-            batch_encoder,
-            galois_engine,
-            n1,
-            n2,
-        )?;
-
-        // Second half: M3*v1 + M2*v2
-        let mut mat3 = mat1;
-        mat3.set_row_offset(current_row_offset + dim_half);
-        let mut mat2 = mat4;
-        mat2.set_row_offset(current_row_offset);
-        mat2.set_col_offset(current_col_offset + dim_half);
-        Self::babystep_giantstep_two_matrices(
-            &mut ctxt2,
-            &mat3,
-            &mat2,
-            batch_encoder,
-            galois_engine,
-            n1,
-            n2,
-        )?;
+        // First half: M1*v1 + M4*v2, second half: M3*v1 + M2*v2
+        let (res1, res2) = Self::join(
+            || Self::babystep_giantstep_encoded(ctxt, &matrix.first, galois_engine),
+            || Self::babystep_giantstep_encoded(&mut ctxt2, &matrix.second, galois_engine),
+        );
+        res1?;
+        res2?;
 
         // Combine results: ctxt + swapped(ctxt2)
         galois_engine.rotate_ctxt_columns(&mut ctxt2)?;
         ctxt.ctxt_add_inplace(&ctxt2)
     }
 
-    pub fn bsgs_multiple_of_packsize<F: PrimeField, T: SquareMatrix<F>>(
+    pub fn bsgs_multiple_of_packsize<F: PrimeField, T: SquareMatrix<F> + MaybeSync>(
         ctxts: &[Ctxt],
         matrix: &T,
         batch_encoder: &BatchEncoder<F>,
@@ -250,26 +234,41 @@ impl Bsgs {
     ) -> Result<Vec<Ctxt>, Error> {
         let num_ciphertexts = ctxts.len();
         let dim = batch_encoder.slot_count();
-        let mut matrix = matrix.to_owned();
-
-        let mut result = Vec::with_capacity(num_ciphertexts);
 
-        for row in 0..num_ciphertexts {
-            matrix.set_row_offset(row * dim);
-            matrix.set_col_offset(0);
-            let mut sum_ctxt = ctxts[0].ctxt_clone()?;
-            Self::fully_packed_bsgs(&mut sum_ctxt, &matrix, batch_encoder, galois_engine)?;
-
-            for (col, ctxt) in ctxts.iter().enumerate().skip(1) {
+        Self::map_indices(num_ciphertexts, |row| {
+            let products = Self::map_indices(num_ciphertexts, |col| {
+                let mut matrix = matrix.to_owned();
+                matrix.set_row_offset(row * dim);
                 matrix.set_col_offset(col * dim);
-                let mut in_out_ctxt = ctxt.ctxt_clone()?;
+                let mut in_out_ctxt = ctxts[col].ctxt_clone()?;
                 Self::fully_packed_bsgs(&mut in_out_ctxt, &matrix, batch_encoder, galois_engine)?;
-                sum_ctxt.ctxt_add_inplace(&in_out_ctxt)?;
-            }
-            result.push(sum_ctxt);
-        }
+                Ok(in_out_ctxt)
+            })?;
+            Self::sum_in_order(products)
+        })
+    }
 
-        Ok(result)
+    /// Like [`Bsgs::bsgs_multiple_of_packsize`], with the blocks produced by [`EncodedFullyPackedMatrix::blocks`].
+    pub fn bsgs_multiple_of_packsize_encoded(
+        ctxts: &[Ctxt],
+        blocks: &[EncodedFullyPackedMatrix],
+        galois_engine: &GaloisEngine,
+    ) -> Result<Vec<Ctxt>, Error> {
+        let num_ciphertexts = ctxts.len();
+        assert_eq!(blocks.len(), num_ciphertexts * num_ciphertexts);
+
+        Self::map_indices(num_ciphertexts, |row| {
+            let products = Self::map_indices(num_ciphertexts, |col| {
+                let mut in_out_ctxt = ctxts[col].ctxt_clone()?;
+                Self::fully_packed_bsgs_encoded(
+                    &mut in_out_ctxt,
+                    &blocks[row * num_ciphertexts + col],
+                    galois_engine,
+                )?;
+                Ok(in_out_ctxt)
+            })?;
+            Self::sum_in_order(products)
+        })
     }
 
     pub fn bsgs_indices(n1: usize, n2: usize, slots: usize) -> Vec<i32> {
@@ -290,6 +289,63 @@ impl Bsgs {
 
         result
     }
+
+    /// Splits `dim` into n1 * n2 with n2 = 2^(floor(log2(dim)) / 2), as done by the fully packed variants.
+    pub fn default_split(dim: usize) -> (usize, usize) {
+        let n2 = 1 << (dim.ilog2() >> 1);
+        (dim / n2, n2)
+    }
+
+    /// The exact set of Galois steps required to apply a `dim` x `dim` matrix with the given packing to ciphertexts with `slots` slots. Fails with [`Error::InvalidParameters`] if the packing does not support `dim` and `slots`.
+    ///
+    /// With only these keys the baby steps are computed as a chain of -1 rotations, see [`Bsgs::hoisted_galois_steps`] for the faster key set.
+    pub fn galois_steps(
+        dim: usize,
+        slots: usize,
+        packing: BsgsPacking,
+    ) -> Result<BTreeSet<i32>, Error> {
+        match packing {
+            BsgsPacking::Packed => {
+                if dim == 0 || (dim << 1 != slots && dim << 2 > slots) {
+                    return Err(Error::InvalidParameters(format!(
+                        "Packed Bsgs needs dim = slots / 2 or dim <= slots / 4, got dim = {dim} and {slots} slots"
+                    )));
+                }
+                let (n1, n2) = Self::default_split(dim);
+                Ok(Self::bsgs_indices(n1, n2, slots).into_iter().collect())
+            }
+            BsgsPacking::FullyPacked | BsgsPacking::MultipleOfPacksize => {
+                if slots < 2 || dim == 0 || dim % slots != 0 {
+                    return Err(Error::InvalidParameters(format!(
+                        "{packing:?} Bsgs needs dim to be a multiple of the slots, got dim = {dim} and {slots} slots"
+                    )));
+                }
+                let (n1, n2) = Self::default_split(slots >> 1);
+                let mut steps: BTreeSet<_> =
+                    Self::bsgs_indices(n1, n2, slots).into_iter().collect();
+                // Column rotation to combine the two halves
+                steps.insert(0);
+                Ok(steps)
+            }
+        }
+    }
+
+    /// Like [`Bsgs::galois_steps`], plus a key for every baby step such that they are computed from one hoisted decomposition (see [`GaloisEngine::hoist`]). Trades n1 - 2 additional keys for faster baby steps.
+    pub fn hoisted_galois_steps(
+        dim: usize,
+        slots: usize,
+        packing: BsgsPacking,
+    ) -> Result<BTreeSet<i32>, Error> {
+        let mut steps = Self::galois_steps(dim, slots, packing)?;
+        let n1 = match packing {
+            BsgsPacking::Packed => Self::default_split(dim).0,
+            BsgsPacking::FullyPacked | BsgsPacking::MultipleOfPacksize => {
+                Self::default_split(slots >> 1).0
+            }
+        };
+        steps.extend((1..n1).map(|j| -(j as i32)));
+        Ok(steps)
+    }
 }
 #[cfg(test)]
 mod test {
@@ -316,6 +372,72 @@ mod test {
             .collect()
     }
 
+    #[test]
+    fn galois_steps_test() {
+        let slots = N;
+
+        // dim << 2 <= slots
+        let dim = N >> 2;
+        let (n1, n2) = Bsgs::default_split(dim);
+        assert_eq!(n1 * n2, dim);
+        let steps = Bsgs::galois_steps(dim, slots, BsgsPacking::Packed).unwrap();
+        assert_eq!(steps.len(), n2 + 1);
+        assert!(steps.contains(&(dim as i32)));
+        assert!(steps.contains(&-1));
+        assert!(!steps.contains(&0));
+
+        // dim << 1 == slots
+        let dim = N >> 1;
+        let (n1, n2) = Bsgs::default_split(dim);
+        let steps = Bsgs::galois_steps(dim, slots, BsgsPacking::Packed).unwrap();
+        let expected: BTreeSet<_> = Bsgs::bsgs_indices(n1, n2, slots).into_iter().collect();
+        assert_eq!(steps, expected);
+        assert!(!steps.contains(&(dim as i32)));
+
+        // Fully packed and multiples of it need the same steps, plus the column rotation
+        let fully = Bsgs::galois_steps(slots, slots, BsgsPacking::FullyPacked).unwrap();
+        let multiple =
+            Bsgs::galois_steps(slots << 2, slots, BsgsPacking::MultipleOfPacksize).unwrap();
+        let mut expected = steps;
+        expected.insert(0);
+        assert_eq!(fully, expected);
+        assert_eq!(multiple, expected);
+
+        // Hoisting adds all baby steps
+        let hoisted = Bsgs::hoisted_galois_steps(dim, slots, BsgsPacking::Packed).unwrap();
+        assert_eq!(hoisted.len(), n2 + n1 - 2);
+        assert!((1..n1 as i32).all(|j| hoisted.contains(&-j)));
+        assert!(hoisted.is_superset(&Bsgs::galois_steps(dim, slots, BsgsPacking::Packed).unwrap()));
+
+        // Incompatible dimensions
+        for (dim, packing) in [
+            (0, BsgsPacking::Packed),
+            (slots, BsgsPacking::Packed),
+            (3 * (slots >> 3), BsgsPacking::Packed),
+            (slots >> 1, BsgsPacking::FullyPacked),
+            (slots + 1, BsgsPacking::MultipleOfPacksize),
+        ] {
+            assert!(matches!(
+                Bsgs::galois_steps(dim, slots, packing),
+                Err(Error::InvalidParameters(_))
+            ));
+            assert!(matches!(
+                Bsgs::hoisted_galois_steps(dim, slots, packing),
+                Err(Error::InvalidParameters(_))
+            ));
+        }
+
+        assert_eq!(BsgsPacking::for_dimension(dim, slots), BsgsPacking::Packed);
+        assert_eq!(
+            BsgsPacking::for_dimension(slots, slots),
+            BsgsPacking::FullyPacked
+        );
+        assert_eq!(
+            BsgsPacking::for_dimension(slots << 1, slots),
+            BsgsPacking::MultipleOfPacksize
+        );
+    }
+
     #[test]
     fn bsgs_test() {
         // let dim = N >> 1;
@@ -350,10 +472,70 @@ mod test {
         for index in Bsgs::bsgs_indices(n1, n2, N) {
             galois.generate_key_for_step(&seckey, index).unwrap();
         }
+        // Baby steps as a chain of -1 rotations
+        assert!(!Bsgs::hoists_baby_steps(&galois, n1));
+
+        let encoded = EncodedPtxt::encode(&vec, &batch_encoder).unwrap();
+        let mut ctxt = pubkey.packed_encrypt(&encoded).unwrap();
+
+        Bsgs::babystep_giantstep(&mut ctxt, &mat, &batch_encoder, &galois, n1, n2).unwrap();
+
+        let decrypted = seckey.packed_decrypt(&ctxt).unwrap();
+        let decoded = decrypted.decode(&batch_encoder).unwrap();
+        assert_eq!(expected, &decoded[..dim]);
+    }
+
+    #[test]
+    fn bsgs_hoisted_test() {
+        let dim = 200;
+        let n1 = 20;
+        let n2 = 10;
+        let mut rng = thread_rng();
+
+        let vec = (0..dim)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let mat = (0..dim)
+            .map(|_| {
+                (0..dim)
+                    .map(|_| ark_bn254::Fr::rand(&mut rng))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let expected = plain_mat_vec(&mat, &vec);
+
+        // HE
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context = Context::build(M as CLong, &p, BITS).unwrap();
+        let mut galois = GaloisEngine::build(M as CLong).unwrap();
+        let seckey = SecKey::build(&context).unwrap();
+        let pubkey = PubKey::from_seckey(&seckey).unwrap();
+        let batch_encoder = BatchEncoder::new(M as CLong);
+
+        // Keys for all baby steps, such that they are computed from one decomposition
+        let baby_steps = (1..n1).map(|j| -(j as i32));
+        galois
+            .generate_missing_keys(
+                &seckey,
+                Bsgs::bsgs_indices(n1, n2, N).into_iter().chain(baby_steps),
+            )
+            .unwrap();
 
         let encoded = EncodedPtxt::encode(&vec, &batch_encoder).unwrap();
         let mut ctxt = pubkey.packed_encrypt(&encoded).unwrap();
 
+        // The baby steps are exactly the hoisted rotations
+        assert!(Bsgs::hoists_baby_steps(&galois, n1));
+        let hoisted = galois.hoist(&ctxt).unwrap();
+        let rot = Bsgs::baby_steps(&ctxt, &galois, n1).unwrap();
+        assert_eq!(rot.len(), n1);
+        assert_eq!(rot[0].to_bytes().unwrap(), ctxt.to_bytes().unwrap());
+        for (j, rot) in rot.iter().enumerate().skip(1) {
+            let expected = galois.rotate_hoisted(&hoisted, -(j as i32)).unwrap();
+            assert_eq!(rot.to_bytes().unwrap(), expected.to_bytes().unwrap());
+        }
+
         Bsgs::babystep_giantstep(&mut ctxt, &mat, &batch_encoder, &galois, n1, n2).unwrap();
 
         let decrypted = seckey.packed_decrypt(&ctxt).unwrap();
@@ -361,6 +543,122 @@ mod test {
         assert_eq!(expected, &decoded[..dim]);
     }
 
+    // The serial path written out: the operations of babystep_giantstep_inner in index order
+    fn serial_bsgs(ctxt: &Ctxt, matrix: &EncodedMatrix, galois: &GaloisEngine) -> Ctxt {
+        let (n1, n2) = (matrix.n1, matrix.n2);
+        let dim = n1 * n2;
+        let mut ctxt = ctxt.ctxt_clone().unwrap();
+        if matrix.slots != dim << 1 {
+            let mut state_rot = ctxt.ctxt_clone().unwrap();
+            galois.rotate_ctxt(&mut state_rot, dim as i32).unwrap();
+            ctxt.ctxt_add_inplace(&state_rot).unwrap();
+        }
+
+        let mut rot = vec![ctxt.ctxt_clone().unwrap()];
+        if Bsgs::hoists_baby_steps(galois, n1) {
+            let hoisted = galois.hoist(&ctxt).unwrap();
+            for j in 1..n1 {
+                rot.push(galois.rotate_hoisted(&hoisted, -(j as i32)).unwrap());
+            }
+        } else {
+            for j in 1..n1 {
+                let mut tmp = ctxt.ctxt_clone().unwrap();
+                galois.rotate_ctxt(&mut tmp, -(j as i32)).unwrap();
+                rot.push(tmp);
+            }
+        }
+
+        let mut result: Option<Ctxt> = None;
+        for k in 0..n2 {
+            let mut inner_sum = rot[0]
+                .ctxt_mul_by_packed_constant(&matrix.diags[k * n1])
+                .unwrap();
+            for (j, rot) in rot.iter().enumerate().skip(1) {
+                let tmp = rot
+                    .ctxt_mul_by_packed_constant(&matrix.diags[k * n1 + j])
+                    .unwrap();
+                inner_sum.ctxt_add_inplace(&tmp).unwrap();
+            }
+            if k != 0 {
+                galois
+                    .rotate_ctxt(&mut inner_sum, -((k * n1) as i32))
+                    .unwrap();
+            }
+            match result.as_mut() {
+                Some(sum) => sum.ctxt_add_inplace(&inner_sum).unwrap(),
+                None => result = Some(inner_sum),
+            }
+        }
+        result.unwrap()
+    }
+
+    #[test]
+    fn bsgs_matches_serial_path() {
+        let dim = 200;
+        let n1 = 20;
+        let n2 = 10;
+        let mut rng = thread_rng();
+
+        let vec = (0..dim)
+            .map(|_| ark_bn254::Fr::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let mat = (0..dim)
+            .map(|_| {
+                (0..dim)
+                    .map(|_| ark_bn254::Fr::rand(&mut rng))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context = Context::build(M as CLong, &p, BITS).unwrap();
+        let seckey = SecKey::build(&context).unwrap();
+        let pubkey = PubKey::from_seckey(&seckey).unwrap();
+        let batch_encoder = BatchEncoder::new(M as CLong);
+        let encoded_mat = EncodedMatrix::new(&mat, &batch_encoder, n1, n2).unwrap();
+
+        // Hoisted baby steps
+        let mut hoisting = GaloisEngine::build(M as CLong).unwrap();
+        let baby_steps = (1..n1).map(|j| -(j as i32));
+        hoisting
+            .generate_missing_keys(
+                &seckey,
+                Bsgs::bsgs_indices(n1, n2, N).into_iter().chain(baby_steps),
+            )
+            .unwrap();
+        assert!(Bsgs::hoists_baby_steps(&hoisting, n1));
+
+        // Independent, composed baby steps
+        let mut composing = GaloisEngine::build(M as CLong).unwrap();
+        composing.generate_power_of_two_keys(&seckey).unwrap();
+        assert!(!Bsgs::hoists_baby_steps(&composing, n1));
+
+        let encoded = EncodedPtxt::encode(&vec, &batch_encoder).unwrap();
+        let ctxt = pubkey.packed_encrypt(&encoded).unwrap();
+
+        for galois in [&hoisting, &composing] {
+            let expected = serial_bsgs(&ctxt, &encoded_mat, galois).to_bytes().unwrap();
+            let mut result = ctxt.clone();
+            Bsgs::babystep_giantstep_encoded(&mut result, &encoded_mat, galois).unwrap();
+            assert_eq!(result.to_bytes().unwrap(), expected);
+
+            // A single thread evaluates everything in the order of the serial path
+            #[cfg(feature = "parallel")]
+            for threads in [1, 8] {
+                let pool = rayon::ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()
+                    .unwrap();
+                let result = pool.install(|| {
+                    let mut result = ctxt.clone();
+                    Bsgs::babystep_giantstep_encoded(&mut result, &encoded_mat, galois).unwrap();
+                    result.to_bytes().unwrap()
+                });
+                assert_eq!(result, expected);
+            }
+        }
+    }
+
     #[test]
     fn bsgs_two_mats_test() {
         // let dim = N >> 1;
@@ -480,6 +778,47 @@ mod test {
         assert_eq!(expected, decoded);
     }
 
+    #[test]
+    fn bsgs_encoded_reuse_test() {
+        let dim = N >> 2;
+        let n2 = 1 << (dim.ilog2() >> 1);
+        let n1 = dim / n2;
+        let root = FFTMatrix::get_minimal_root(dim);
+        let mut rng = thread_rng();
+
+        let ntt_proc = NTTProcessor::new(dim, root);
+
+        // HE
+        let p = ZZ::char::<ark_bn254::Fr>().unwrap();
+        let context = Context::build(M as CLong, &p, BITS).unwrap();
+        let mut galois = GaloisEngine::build(M as CLong).unwrap();
+        let seckey = SecKey::build(&context).unwrap();
+        let pubkey = PubKey::from_seckey(&seckey).unwrap();
+        let batch_encoder = BatchEncoder::new(M as CLong);
+
+        for index in Bsgs::bsgs_indices(n1, n2, N) {
+            galois.generate_key_for_step(&seckey, index).unwrap();
+        }
+
+        // Encoded once, applied to multiple ciphertexts
+        let mat = EncodedMatrix::new(&FFTMatrix::new(dim, root), &batch_encoder, n1, n2).unwrap();
+        for _ in 0..2 {
+            let mut vec = (0..dim)
+                .map(|_| ark_bn254::Fr::rand(&mut rng))
+                .collect::<Vec<_>>();
+            let encoded = EncodedPtxt::encode(&vec, &batch_encoder).unwrap();
+            let mut ctxt = pubkey.packed_encrypt(&encoded).unwrap();
+            Bsgs::babystep_giantstep_encoded(&mut ctxt, &mat, &galois).unwrap();
+
+            let decrypted = seckey.packed_decrypt(&ctxt).unwrap();
+            let decoded = decrypted.decode(&batch_encoder).unwrap();
+
+            // plain
+            ntt_proc.fft_inplace(&mut vec);
+            assert_eq!(vec, &decoded[..dim]);
+        }
+    }
+
     fn packed_ntt_test<F: PrimeField>(root: F, divisor: usize) {
         let dim = N >> divisor;
         let n2 = 1 << (dim.ilog2() >> 1);
@@ -0,0 +1,200 @@
+use super::{Bsgs, MaybeSync, SquareMatrix};
+use crate::{encoding::batch_encoder::BatchEncoder, helib::error::Error, EncodedPtxt};
+use ark_ff::PrimeField;
+
+/// The diagonals of a matrix (or of two matrices packed into both halves of the slots), encoded once for the babystep-giantstep algorithm. Can be applied to many ciphertexts with [`Bsgs::babystep_giantstep_encoded`].
+pub struct EncodedMatrix {
+    pub(crate) diags: Vec<EncodedPtxt>,
+    pub(crate) n1: usize,
+    pub(crate) n2: usize,
+    pub(crate) slots: usize,
+}
+
+impl EncodedMatrix {
+    /// Encodes the leading n1*n2 x n1*n2 block of `matrix`, see [`Bsgs::babystep_giantstep`].
+    pub fn new<F: PrimeField, T: SquareMatrix<F> + MaybeSync>(
+        matrix: &T,
+        batch_encoder: &BatchEncoder<F>,
+        n1: usize,
+        n2: usize,
+    ) -> Result<Self, Error> {
+        let diags = Self::encode_one_matrix(matrix, batch_encoder, n1, n2)?;
+        Ok(Self {
+            diags,
+            n1,
+            n2,
+            slots: batch_encoder.slot_count(),
+        })
+    }
+
+    /// Encodes `matrix1` into the first and `matrix2` into the second half of the slots, see [`Bsgs::babystep_giantstep_two_matrices`].
+    pub fn from_two_matrices<F: PrimeField, T: SquareMatrix<F> + MaybeSync>(
+        matrix1: &T,
+        matrix2: &T,
+        batch_encoder: &BatchEncoder<F>,
+        n1: usize,
+        n2: usize,
+    ) -> Result<Self, Error> {
+        let diags = Self::encode_two_matrices(matrix1, matrix2, batch_encoder, n1, n2)?;
+        Ok(Self {
+            diags,
+            n1,
+            n2,
+            slots: batch_encoder.slot_count(),
+        })
+    }
+
+    pub fn n1(&self) -> usize {
+        self.n1
+    }
+
+    pub fn n2(&self) -> usize {
+        self.n2
+    }
+
+    pub fn slot_count(&self) -> usize {
+        self.slots
+    }
+
+    fn encode_one_matrix<F: PrimeField, T: SquareMatrix<F> + MaybeSync>(
+        matrix: &T,
+        batch_encoder: &BatchEncoder<F>,
+        n1: usize,
+        n2: usize,
+    ) -> Result<Vec<EncodedPtxt>, Error> {
+        let dim = n1 * n2;
+        assert!(dim <= matrix.dimension());
+        let slots = batch_encoder.slot_count();
+        let halfslots = slots >> 1;
+        assert!(dim << 1 == slots || dim << 2 <= slots);
+        assert_eq!(dim, n1 * n2);
+
+        Bsgs::map_indices(dim, |i| {
+            let k = i / n1;
+            let mut diag = Vec::with_capacity(halfslots);
+
+            for j in 0..dim {
+                diag.push(matrix.get(j, (j + dim - i) % dim));
+            }
+            // rotate:
+            if k != 0 {
+                diag.rotate_left(k * n1);
+            }
+            // prepare for non-full-packed rotations
+            if slots != dim << 1 {
+                diag.resize(halfslots, F::zero());
+                for index in 0..k * n1 {
+                    let index_src = dim - 1 - index;
+                    let index_des = halfslots - 1 - index;
+                    diag[index_des] = diag[index_src];
+                    diag[index_src] = F::zero();
+                }
+            }
+            EncodedPtxt::encode(&diag, batch_encoder)
+        })
+    }
+
+    fn encode_two_matrices<F: PrimeField, T: SquareMatrix<F> + MaybeSync>(
+        matrix1: &T,
+        matrix2: &T,
+        batch_encoder: &BatchEncoder<F>,
+        n1: usize,
+        n2: usize,
+    ) -> Result<Vec<EncodedPtxt>, Error> {
+        let dim = n1 * n2;
+        assert!(dim <= matrix1.dimension());
+        assert!(dim <= matrix2.dimension());
+        let slots = batch_encoder.slot_count();
+        let halfslots = slots >> 1;
+        assert!(dim << 1 == slots || dim << 2 <= slots);
+
+        Bsgs::map_indices(dim, |i| {
+            let k = i / n1;
+            let mut diag = Vec::with_capacity(slots);
+            let mut tmp = Vec::with_capacity(dim);
+
+            for j in 0..dim {
+                diag.push(matrix1.get(j, (j + dim - i) % dim));
+                tmp.push(matrix2.get(j, (j + dim - i) % dim));
+            }
+            // rotate:
+            if k != 0 {
+                diag.rotate_left(k * n1);
+                tmp.rotate_left(k * n1);
+            }
+            // prepare for non-full-packed rotations
+            if slots != dim << 1 {
+                diag.resize(halfslots, F::zero());
+                tmp.resize(halfslots, F::zero());
+                for index in 0..k * n1 {
+                    let index_src = dim - 1 - index;
+                    let index_des = halfslots - 1 - index;
+                    diag[index_des] = diag[index_src];
+                    tmp[index_des] = tmp[index_src];
+                    diag[index_src] = F::zero();
+                    tmp[index_src] = F::zero();
+                }
+            }
+            diag.resize(slots, F::zero());
+            diag[halfslots..slots].copy_from_slice(&tmp[..(slots - halfslots)]);
+            EncodedPtxt::encode(&diag, batch_encoder)
+        })
+    }
+}
+
+/// A slot_count x slot_count matrix encoded for [`Bsgs::fully_packed_bsgs_encoded`].
+pub struct EncodedFullyPackedMatrix {
+    // M1 and M4 of the split M = [M1, M2] [M3, M4]
+    pub(crate) first: EncodedMatrix,
+    // M3 and M2 of the split M = [M1, M2] [M3, M4]
+    pub(crate) second: EncodedMatrix,
+}
+
+impl EncodedFullyPackedMatrix {
+    /// Encodes the slot_count x slot_count block of `matrix` at its current row and column offset.
+    pub fn new<F: PrimeField, T: SquareMatrix<F> + MaybeSync>(
+        matrix: &T,
+        batch_encoder: &BatchEncoder<F>,
+    ) -> Result<Self, Error> {
+        let dim_half = batch_encoder.slot_count() >> 1;
+        let (n1, n2) = Bsgs::default_split(dim_half);
+
+        let current_col_offset = matrix.get_col_offset();
+        let current_row_offset = matrix.get_row_offset();
+
+        let mat1 = matrix.clone();
+        let mut mat4 = matrix.clone();
+        mat4.set_col_offset(current_col_offset + dim_half);
+        mat4.set_row_offset(current_row_offset + dim_half);
+
+        let mut mat3 = mat1.clone();
+        mat3.set_row_offset(current_row_offset + dim_half);
+        let mut mat2 = mat4.clone();
+        mat2.set_row_offset(current_row_offset);
+        mat2.set_col_offset(current_col_offset + dim_half);
+
+        let (first, second) = Bsgs::join(
+            || EncodedMatrix::from_two_matrices(&mat1, &mat4, batch_encoder, n1, n2),
+            || EncodedMatrix::from_two_matrices(&mat3, &mat2, batch_encoder, n1, n2),
+        );
+        Ok(Self {
+            first: first?,
+            second: second?,
+        })
+    }
+
+    /// Encodes all num_blocks x num_blocks blocks of `matrix` in row-major order, see [`Bsgs::bsgs_multiple_of_packsize_encoded`].
+    pub fn blocks<F: PrimeField, T: SquareMatrix<F> + MaybeSync>(
+        matrix: &T,
+        batch_encoder: &BatchEncoder<F>,
+        num_blocks: usize,
+    ) -> Result<Vec<Self>, Error> {
+        let dim = batch_encoder.slot_count();
+        Bsgs::map_indices(num_blocks * num_blocks, |i| {
+            let mut matrix = matrix.to_owned();
+            matrix.set_row_offset((i / num_blocks) * dim);
+            matrix.set_col_offset((i % num_blocks) * dim);
+            Self::new(&matrix, batch_encoder)
+        })
+    }
+}
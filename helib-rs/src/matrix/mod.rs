@@ -1,4 +1,5 @@
 pub(crate) mod bsgs;
+pub(crate) mod encoded_matrix;
 
 use crate::{
     encoding::{galois::Galois, ntt::NTTProcessor},
@@ -8,7 +9,21 @@ use crate::{
 use ark_ff::PrimeField;
 use std::sync::Arc;
 
-pub use bsgs::Bsgs;
+pub use bsgs::{Bsgs, BsgsPacking};
+pub use encoded_matrix::{EncodedFullyPackedMatrix, EncodedMatrix};
+
+/// Additional bound on the matrices of the Bsgs functions: `Send + Sync` with the `parallel` feature, since the diagonals are then read from several threads, and no bound otherwise.
+#[cfg(feature = "parallel")]
+pub trait MaybeSync: Send + Sync {}
+
+#[cfg(feature = "parallel")]
+impl<T: Send + Sync + ?Sized> MaybeSync for T {}
+
+#[cfg(not(feature = "parallel"))]
+pub trait MaybeSync {}
+
+#[cfg(not(feature = "parallel"))]
+impl<T: ?Sized> MaybeSync for T {}
 
 pub fn plain_matrix_ctxt_vector<F: PrimeField, T: SquareMatrix<F>>(
     mat: &T,